@@ -0,0 +1,354 @@
+//! Advent of Code 2025 - arbitrary-precision unsigned integers
+//!
+//! Some puzzle answers (long multiplication columns in Day 6, timeline counts
+//! in Day 7) can outgrow `u128`. `BigUint` covers what those puzzles need:
+//! addition, multiplication, subtraction, division, powers, parsing and
+//! printing in decimal. It is written for clarity rather than speed.
+
+use std::cmp::Ordering;
+use std::fmt;
+use std::iter::{Product, Sum};
+use std::ops::{Add, AddAssign, Mul};
+use std::str::FromStr;
+
+/// An unsigned integer of any size.
+///
+/// Stored as base 2^32 limbs, least significant first, with no trailing zero
+/// limbs, so zero is the empty vector and every value has one representation.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct BigUint {
+    limbs: Vec<u32>,
+}
+
+/// Largest power of ten that fits in a limb, used for decimal conversion
+const DECIMAL_BASE: u32 = 1_000_000_000;
+const DECIMAL_DIGITS: usize = 9;
+
+impl BigUint {
+    pub fn zero() -> Self {
+        BigUint { limbs: Vec::new() }
+    }
+
+    pub fn one() -> Self {
+        BigUint { limbs: vec![1] }
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.limbs.is_empty()
+    }
+
+    /// Number of significant bits (0 for zero)
+    pub fn bits(&self) -> u64 {
+        match self.limbs.last() {
+            Some(&top) => (self.limbs.len() as u64 - 1) * 32 + (32 - top.leading_zeros()) as u64,
+            None => 0,
+        }
+    }
+
+    /// The value as a `u128`, if it fits
+    pub fn to_u128(&self) -> Option<u128> {
+        if self.limbs.len() > 4 {
+            return None;
+        }
+        Some(self.limbs.iter().rev().fold(0, |acc, &limb| acc << 32 | limb as u128))
+    }
+
+    /// The nearest `f64`, for ratios and plotting; infinite past `f64::MAX`
+    pub fn to_f64(&self) -> f64 {
+        // The top 64 bits carry more precision than an f64 mantissa holds
+        let bits = self.bits();
+        let shift = bits.saturating_sub(64);
+        let top = (0..bits - shift).fold(0u64, |acc, i| acc | (self.bit(shift + i) as u64) << i);
+        top as f64 * 2f64.powi(shift.min(i32::MAX as u64) as i32)
+    }
+
+    /// `self - other`, or `None` if that would be negative
+    pub fn checked_sub(&self, other: &Self) -> Option<Self> {
+        if *self < *other {
+            return None;
+        }
+
+        let mut limbs = Vec::with_capacity(self.limbs.len());
+        let mut borrow = 0i64;
+        for (i, &limb) in self.limbs.iter().enumerate() {
+            let mut diff = limb as i64 - other.limbs.get(i).copied().unwrap_or(0) as i64 - borrow;
+            borrow = 0;
+            if diff < 0 {
+                diff += 1 << 32;
+                borrow = 1;
+            }
+            limbs.push(diff as u32);
+        }
+        Some(BigUint::normalized(limbs))
+    }
+
+    /// Quotient and remainder of `self / divisor`, or `None` when dividing by zero
+    pub fn div_rem(&self, divisor: &Self) -> Option<(Self, Self)> {
+        match divisor.limbs.as_slice() {
+            [] => None,
+            &[d] => {
+                let (quotient, remainder) = self.div_rem_small(d);
+                Some((quotient, BigUint::from(remainder)))
+            }
+            _ => Some(self.div_rem_binary(divisor)),
+        }
+    }
+
+    /// `self` raised to `exp`
+    pub fn pow(&self, mut exp: u32) -> Self {
+        let mut base = self.clone();
+        let mut result = BigUint::one();
+        while exp > 0 {
+            if exp & 1 == 1 {
+                result = &result * &base;
+            }
+            exp >>= 1;
+            if exp > 0 {
+                base = &base * &base;
+            }
+        }
+        result
+    }
+
+    /// Division by a single limb, one limb at a time from the top
+    fn div_rem_small(&self, divisor: u32) -> (Self, u32) {
+        let mut quotient = vec![0; self.limbs.len()];
+        let mut remainder = 0u64;
+        for (i, &limb) in self.limbs.iter().enumerate().rev() {
+            let current = remainder << 32 | limb as u64;
+            quotient[i] = (current / divisor as u64) as u32;
+            remainder = current % divisor as u64;
+        }
+        (BigUint::normalized(quotient), remainder as u32)
+    }
+
+    /// Shift-and-subtract long division, one bit at a time
+    fn div_rem_binary(&self, divisor: &Self) -> (Self, Self) {
+        let mut quotient = vec![0u32; self.limbs.len()];
+        let mut remainder = BigUint::zero();
+
+        for bit in (0..self.bits()).rev() {
+            remainder = remainder.shl1(self.bit(bit));
+            if remainder >= *divisor {
+                remainder = remainder.checked_sub(divisor).unwrap();
+                quotient[(bit / 32) as usize] |= 1 << (bit % 32);
+            }
+        }
+        (BigUint::normalized(quotient), remainder)
+    }
+
+    fn bit(&self, bit: u64) -> bool {
+        self.limbs[(bit / 32) as usize] >> (bit % 32) & 1 == 1
+    }
+
+    /// `self * 2 + low`
+    fn shl1(mut self, low: bool) -> Self {
+        let mut carry = low as u32;
+        for limb in &mut self.limbs {
+            let next = *limb >> 31;
+            *limb = *limb << 1 | carry;
+            carry = next;
+        }
+        if carry != 0 {
+            self.limbs.push(carry);
+        }
+        self
+    }
+
+    /// `self * factor + addend` for single-limb values, in place
+    fn mul_add_small(&mut self, factor: u32, addend: u32) {
+        let mut carry = addend as u64;
+        for limb in &mut self.limbs {
+            let product = *limb as u64 * factor as u64 + carry;
+            *limb = product as u32;
+            carry = product >> 32;
+        }
+        if carry != 0 {
+            self.limbs.push(carry as u32);
+        }
+    }
+
+    /// Drop trailing zero limbs so equal values compare equal
+    fn normalized(mut limbs: Vec<u32>) -> Self {
+        while limbs.last() == Some(&0) {
+            limbs.pop();
+        }
+        BigUint { limbs }
+    }
+}
+
+impl From<u128> for BigUint {
+    fn from(mut value: u128) -> Self {
+        let mut limbs = Vec::new();
+        while value != 0 {
+            limbs.push(value as u32);
+            value >>= 32;
+        }
+        BigUint { limbs }
+    }
+}
+
+impl From<u64> for BigUint {
+    fn from(value: u64) -> Self {
+        BigUint::from(value as u128)
+    }
+}
+
+impl From<u32> for BigUint {
+    fn from(value: u32) -> Self {
+        BigUint::from(value as u128)
+    }
+}
+
+impl Ord for BigUint {
+    fn cmp(&self, other: &Self) -> Ordering {
+        // No trailing zeros, so more limbs means a larger value
+        self.limbs
+            .len()
+            .cmp(&other.limbs.len())
+            .then_with(|| self.limbs.iter().rev().cmp(other.limbs.iter().rev()))
+    }
+}
+
+impl PartialOrd for BigUint {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl AddAssign<&BigUint> for BigUint {
+    fn add_assign(&mut self, other: &BigUint) {
+        if self.limbs.len() < other.limbs.len() {
+            self.limbs.resize(other.limbs.len(), 0);
+        }
+
+        let mut carry = 0u64;
+        for (i, limb) in self.limbs.iter_mut().enumerate() {
+            let sum = *limb as u64 + other.limbs.get(i).copied().unwrap_or(0) as u64 + carry;
+            *limb = sum as u32;
+            carry = sum >> 32;
+            if carry == 0 && i >= other.limbs.len() {
+                break;
+            }
+        }
+        if carry != 0 {
+            self.limbs.push(carry as u32);
+        }
+    }
+}
+
+impl Add<&BigUint> for &BigUint {
+    type Output = BigUint;
+
+    fn add(self, other: &BigUint) -> BigUint {
+        let mut sum = self.clone();
+        sum += other;
+        sum
+    }
+}
+
+impl Add for BigUint {
+    type Output = BigUint;
+
+    fn add(mut self, other: BigUint) -> BigUint {
+        self += &other;
+        self
+    }
+}
+
+impl Mul<&BigUint> for &BigUint {
+    type Output = BigUint;
+
+    /// Schoolbook multiplication
+    fn mul(self, other: &BigUint) -> BigUint {
+        if self.is_zero() || other.is_zero() {
+            return BigUint::zero();
+        }
+
+        let mut limbs = vec![0u32; self.limbs.len() + other.limbs.len()];
+        for (i, &a) in self.limbs.iter().enumerate() {
+            let mut carry = 0u64;
+            for (j, &b) in other.limbs.iter().enumerate() {
+                let current = limbs[i + j] as u64 + a as u64 * b as u64 + carry;
+                limbs[i + j] = current as u32;
+                carry = current >> 32;
+            }
+            limbs[i + other.limbs.len()] = carry as u32;
+        }
+        BigUint::normalized(limbs)
+    }
+}
+
+impl Mul for BigUint {
+    type Output = BigUint;
+
+    fn mul(self, other: BigUint) -> BigUint {
+        &self * &other
+    }
+}
+
+impl Sum for BigUint {
+    fn sum<I: Iterator<Item = BigUint>>(iter: I) -> Self {
+        iter.fold(BigUint::zero(), |acc, x| acc + x)
+    }
+}
+
+impl Product for BigUint {
+    fn product<I: Iterator<Item = BigUint>>(iter: I) -> Self {
+        iter.fold(BigUint::one(), |acc, x| acc * x)
+    }
+}
+
+/// The string was not a non-empty run of decimal digits
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseBigUintError;
+
+impl fmt::Display for ParseBigUintError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "invalid digit found in string")
+    }
+}
+
+impl std::error::Error for ParseBigUintError {}
+
+impl FromStr for BigUint {
+    type Err = ParseBigUintError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s.is_empty() || !s.bytes().all(|b| b.is_ascii_digit()) {
+            return Err(ParseBigUintError);
+        }
+
+        // Nine digits at a time: value = value * 10^k + chunk
+        let mut value = BigUint::zero();
+        for chunk in s.as_bytes().chunks(DECIMAL_DIGITS) {
+            let digits = chunk.iter().fold(0u32, |acc, &b| acc * 10 + (b - b'0') as u32);
+            value.mul_add_small(10u32.pow(chunk.len() as u32), digits);
+        }
+        value.limbs = BigUint::normalized(value.limbs).limbs;
+        Ok(value)
+    }
+}
+
+impl fmt::Display for BigUint {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.is_zero() {
+            return f.pad_integral(true, "", "0");
+        }
+
+        // Peel off nine decimal digits at a time, least significant first
+        let mut chunks = Vec::new();
+        let mut rest = self.clone();
+        while !rest.is_zero() {
+            let (quotient, remainder) = rest.div_rem_small(DECIMAL_BASE);
+            chunks.push(remainder);
+            rest = quotient;
+        }
+
+        let mut digits = chunks.pop().unwrap().to_string();
+        for chunk in chunks.iter().rev() {
+            digits.push_str(&format!("{:09}", chunk));
+        }
+        f.pad_integral(true, "", &digits)
+    }
+}
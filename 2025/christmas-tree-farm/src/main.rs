@@ -1,3 +1,4 @@
+use sections::{Section, Sections};
 use std::{fs, collections::HashSet};
 
 fn norm(s: Vec<Vec<bool>>) -> Vec<Vec<bool>> {
@@ -54,18 +55,24 @@ fn solve(pv: &[Vec<Vec<(usize, usize)>>], i: usize, g: &mut [bool], w: usize, h:
 
 fn main() {
     let input = fs::read_to_string("input.txt").unwrap();
-    let parts: Vec<_> = input.split("\n\n").collect();
+    // Shape blocks start with an "N:" header; the block of "WxH: counts" lines is the regions
+    let is_region = |l: &str| l.contains('x') && l.contains(':');
+    let sections = Sections::parse(&input).name_by(|s: &Section| {
+        if s.lines.iter().any(|l| is_region(l)) { Some("regions".to_string()) }
+        else { s.lines[0].strip_suffix(':').map(|n| format!("shape {}", n.trim())) }
+    });
+    let regions = sections.get("regions").unwrap_or_else(|e| { eprintln!("{}", e); std::process::exit(1) });
     
-    let shapes: Vec<Vec<Vec<(usize, usize)>>> = parts.iter()
-        .filter(|p| p.contains(':') && !p.contains('x'))
-        .map(|p| {
-            let grid: Vec<Vec<bool>> = p.lines().skip(1)
+    let shapes: Vec<Vec<Vec<(usize, usize)>>> = sections.iter()
+        .filter(|s| s.name.starts_with("shape "))
+        .map(|s| {
+            let grid: Vec<Vec<bool>> = s.lines.iter().skip(1)
                 .map(|l| l.chars().map(|c| c == '#').collect()).collect();
             variants(&norm(grid))
         }).collect();
 
-    let count = parts.iter().flat_map(|p| p.lines())
-        .filter(|l| l.contains('x') && l.contains(':'))
+    let count = regions.lines.iter()
+        .filter(|l| is_region(l))
         .filter(|l| {
             let (dims, cnts) = l.split_once(':').unwrap();
             let (w, h): (usize, usize) = dims.split_once('x').map(|(a, b)| (a.trim().parse().unwrap(), b.trim().parse().unwrap())).unwrap();
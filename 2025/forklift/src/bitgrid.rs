@@ -0,0 +1,296 @@
+use crate::AccessRule;
+use std::io::{self, BufRead};
+
+const WORD: usize = u64::BITS as usize;
+
+/// A paper-roll map packed one bit per cell, 64 cells to a word.
+///
+/// Rows are stored back to back, each padded to a whole number of words, and
+/// the padding bits are always zero. Neighbor counts are never computed cell by
+/// cell: every offset in the rule becomes one shifted copy of a word, and the
+/// copies are summed with a bit-sliced adder so all 64 counts in a word are
+/// built at once. Accessible rolls are then counted with `count_ones`.
+#[derive(Debug, Clone)]
+pub struct BitGrid {
+    rows: usize,
+    cols: usize,
+    words_per_row: usize,
+    bits: Vec<u64>,
+}
+
+impl BitGrid {
+    /// An empty grid of the given size
+    pub fn new(rows: usize, cols: usize) -> Self {
+        let words_per_row = cols.div_ceil(WORD);
+        BitGrid {
+            rows,
+            cols,
+            words_per_row,
+            bits: vec![0; rows * words_per_row],
+        }
+    }
+
+    /// Read a map of '@' rolls line by line, packing each line as it arrives
+    /// so the text of the whole map is never held in memory. Blank lines are
+    /// skipped and every other line must be as wide as the first.
+    pub fn read(reader: impl BufRead) -> io::Result<Self> {
+        let mut grid = BitGrid::new(0, 0);
+
+        for line in reader.lines() {
+            let line = line?;
+            let line = line.trim_end();
+            if line.is_empty() {
+                continue;
+            }
+
+            if grid.rows == 0 {
+                grid = BitGrid::new(0, line.len());
+            } else if line.len() != grid.cols {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    format!("row {} is {} cells wide, expected {}", grid.rows + 1, line.len(), grid.cols),
+                ));
+            }
+
+            let mut row = vec![0u64; grid.words_per_row];
+            for (col, byte) in line.bytes().enumerate() {
+                if byte == b'@' {
+                    row[col / WORD] |= 1 << (col % WORD);
+                }
+            }
+            grid.bits.extend_from_slice(&row);
+            grid.rows += 1;
+        }
+        Ok(grid)
+    }
+
+    pub fn rows(&self) -> usize {
+        self.rows
+    }
+
+    pub fn cols(&self) -> usize {
+        self.cols
+    }
+
+    /// Whether there is a roll at (row, col)
+    pub fn get(&self, row: usize, col: usize) -> bool {
+        self.word(row, col / WORD) >> (col % WORD) & 1 == 1
+    }
+
+    /// Place or remove a roll at (row, col)
+    pub fn set(&mut self, row: usize, col: usize, roll: bool) {
+        let index = row * self.words_per_row + col / WORD;
+        let bit = 1 << (col % WORD);
+        if roll {
+            self.bits[index] |= bit;
+        } else {
+            self.bits[index] &= !bit;
+        }
+    }
+
+    /// Total number of rolls in the grid
+    pub fn count_rolls(&self) -> usize {
+        self.bits.iter().map(|w| w.count_ones() as usize).sum()
+    }
+
+    /// Number of rolls the rule considers accessible, in a single pass
+    pub fn count_accessible(&self, rule: &AccessRule) -> usize {
+        let mut planes = Vec::new();
+        let mut count = 0;
+
+        for row in 0..self.rows {
+            for w in 0..self.words_per_row {
+                count += self.accessible_mask(rule, row, w, &mut planes).count_ones() as usize;
+            }
+        }
+        count
+    }
+
+    /// Remove accessible rolls round by round until none are left.
+    /// Returns the number of rolls removed in each round.
+    ///
+    /// Like the cell-based cascade in `roll_paper`, only words next to a
+    /// removal are looked at again, so each round costs time proportional to
+    /// what changed rather than to the size of the map.
+    pub fn cascade(&mut self, rule: &AccessRule) -> Vec<usize> {
+        let total_words = self.rows * self.words_per_row;
+        let mut planes = Vec::new();
+
+        // Every word is a candidate in the first round
+        let mut dirty: Vec<usize> = (0..total_words).collect();
+        let mut marked = vec![false; total_words];
+        let mut removed_per_round = Vec::new();
+
+        loop {
+            // All masks are computed against the grid as it stood at the start
+            // of the round, then applied together
+            let mut removals: Vec<(usize, u64)> = Vec::new();
+            for &index in &dirty {
+                marked[index] = false;
+                let mask = self.accessible_mask(rule, index / self.words_per_row, index % self.words_per_row, &mut planes);
+                if mask != 0 {
+                    removals.push((index, mask));
+                }
+            }
+
+            if removals.is_empty() {
+                break;
+            }
+
+            let mut removed = 0;
+            for &(index, mask) in &removals {
+                self.bits[index] &= !mask;
+                removed += mask.count_ones() as usize;
+            }
+            removed_per_round.push(removed);
+
+            // Queue every word holding a cell that counts one of the removed rolls
+            dirty.clear();
+            for &(index, _) in &removals {
+                let (row, w) = (index / self.words_per_row, index % self.words_per_row);
+                for &(dr, dc) in rule.offsets() {
+                    for dependent in self.dependent_words(rule, row, w, dr, dc) {
+                        if !marked[dependent] {
+                            marked[dependent] = true;
+                            dirty.push(dependent);
+                        }
+                    }
+                }
+            }
+        }
+
+        removed_per_round
+    }
+
+    /// Bits of word `w` in `row` whose cell holds an accessible roll.
+    /// `planes` is scratch space for the bit-sliced neighbor counter.
+    fn accessible_mask(&self, rule: &AccessRule, row: usize, w: usize, planes: &mut Vec<u64>) -> u64 {
+        let rolls = self.word(row, w);
+        if rolls == 0 {
+            return 0;
+        }
+
+        // planes[k] holds bit k of every cell's neighbor count
+        let width = (usize::BITS - rule.offsets().len().leading_zeros()) as usize;
+        planes.clear();
+        planes.resize(width, 0);
+
+        for &(dr, dc) in rule.offsets() {
+            let Some(r) = self.offset_row(rule, row, dr) else {
+                continue;
+            };
+            let mut carry = self.shifted_word(rule, r, w, dc);
+            for plane in planes.iter_mut() {
+                if carry == 0 {
+                    break;
+                }
+                let sum = *plane ^ carry;
+                carry &= *plane;
+                *plane = sum;
+            }
+        }
+
+        rolls & less_than(planes, rule.threshold)
+    }
+
+    /// Word `w` of `row`, or zero outside the grid
+    fn word(&self, row: usize, w: usize) -> u64 {
+        if row < self.rows && w < self.words_per_row {
+            self.bits[row * self.words_per_row + w]
+        } else {
+            0
+        }
+    }
+
+    /// The row `dr` away from `row`, wrapped or clipped according to the rule
+    fn offset_row(&self, rule: &AccessRule, row: usize, dr: i32) -> Option<usize> {
+        let r = row as i64 + dr as i64;
+        if rule.wrap {
+            Some(r.rem_euclid(self.rows as i64) as usize)
+        } else if r >= 0 && r < self.rows as i64 {
+            Some(r as usize)
+        } else {
+            None
+        }
+    }
+
+    /// A word whose bit i is the cell at column `w * 64 + i + dc` of `row`
+    fn shifted_word(&self, rule: &AccessRule, row: usize, w: usize, dc: i32) -> u64 {
+        let start = (w * WORD) as i64 + dc as i64;
+        let end = start + WORD as i64;
+
+        if rule.wrap && (start < 0 || end > self.cols as i64) {
+            // The window crosses an edge of a torus; only edge words get here
+            let mut word = 0;
+            for i in 0..WORD.min(self.cols - w * WORD) {
+                let col = (start + i as i64).rem_euclid(self.cols as i64) as usize;
+                word |= (self.get(row, col) as u64) << i;
+            }
+            return word;
+        }
+
+        let first = start.div_euclid(WORD as i64);
+        let shift = start.rem_euclid(WORD as i64) as u32;
+        let fetch = |k: i64| if k < 0 { 0 } else { self.word(row, k as usize) };
+
+        let low = fetch(first) >> shift;
+        let high = if shift == 0 { 0 } else { fetch(first + 1) << (WORD as u32 - shift) };
+        (low | high) & self.valid_bits(w)
+    }
+
+    /// Padding bits past the last column must stay clear
+    fn valid_bits(&self, w: usize) -> u64 {
+        let used = self.cols - w * WORD;
+        if used >= WORD { u64::MAX } else { (1 << used) - 1 }
+    }
+
+    /// Words containing a cell that counts some cell of word `w` in `row`
+    /// through offset (dr, dc), i.e. the word moved by (-dr, -dc)
+    fn dependent_words(&self, rule: &AccessRule, row: usize, w: usize, dr: i32, dc: i32) -> Vec<usize> {
+        let Some(r) = self.offset_row(rule, row, -dr) else {
+            return Vec::new();
+        };
+        let base = r * self.words_per_row;
+        let cols = self.cols as i64;
+        let lo = (w * WORD) as i64 - dc as i64;
+        let hi = lo + (WORD.min(self.cols - w * WORD) as i64) - 1;
+
+        let span = |a: i64, b: i64| (a as usize / WORD..=b as usize / WORD).map(move |k| base + k);
+        if rule.wrap {
+            if hi - lo + 1 >= cols {
+                return span(0, cols - 1).collect();
+            }
+            let (a, b) = (lo.rem_euclid(cols), hi.rem_euclid(cols));
+            if a <= b {
+                span(a, b).collect()
+            } else {
+                span(a, cols - 1).chain(span(0, b)).collect()
+            }
+        } else {
+            let (a, b) = (lo.max(0), hi.min(cols - 1));
+            if a > b { Vec::new() } else { span(a, b).collect() }
+        }
+    }
+}
+
+/// Bit mask of the lanes whose bit-sliced count is below `threshold`
+fn less_than(planes: &[u64], threshold: usize) -> u64 {
+    if threshold >> planes.len() != 0 {
+        // Larger than any count the planes can hold
+        return u64::MAX;
+    }
+
+    // Walk from the most significant bit down, tracking lanes still equal
+    // to the threshold's prefix and lanes already known to be smaller
+    let mut less = 0;
+    let mut equal = u64::MAX;
+    for (k, &plane) in planes.iter().enumerate().rev() {
+        if threshold >> k & 1 == 1 {
+            less |= equal & !plane;
+            equal &= plane;
+        } else {
+            equal &= !plane;
+        }
+    }
+    less
+}
@@ -0,0 +1,15 @@
+//! Advent of Code 2025 - Day 4: shared forklift accessibility rules
+//!
+//! Both `printing_department` and `roll_paper` decide whether a paper roll is
+//! accessible by counting the rolls around it. The puzzle uses the 8-direction
+//! Moore neighborhood and "fewer than 4", but other forklift layouts need other
+//! shapes, so the rule lives here and both solvers are written against it.
+//!
+//! `BitGrid` is a bit-packed alternative to `Vec<Vec<char>>` for maps too large
+//! to hold at 4 bytes per cell; it supports the same rules.
+
+mod bitgrid;
+mod rule;
+
+pub use bitgrid::BitGrid;
+pub use rule::{AccessRule, Neighborhood, MAX_RADIUS};
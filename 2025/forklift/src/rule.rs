@@ -0,0 +1,186 @@
+/// Which cells around a roll count as its neighbors
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Neighborhood {
+    /// Every cell within Chebyshev distance `radius` (8 directions at radius 1)
+    Moore,
+    /// Every cell within Manhattan distance `radius` (4 directions at radius 1)
+    VonNeumann,
+    /// An explicit list of (row, col) offsets; the radius is ignored
+    Custom(Vec<(i32, i32)>),
+}
+
+/// The largest `--radius` accepted: a Moore neighborhood of this radius is
+/// already (2 * 100 + 1)² - 1 = 40400 offsets per roll
+pub const MAX_RADIUS: i32 = 100;
+
+/// Decides which paper rolls a forklift can reach.
+///
+/// A roll is accessible when fewer than `threshold` of the cells in its
+/// neighborhood hold a roll. With `wrap` set the grid is treated as a torus,
+/// so offsets that leave one edge come back in on the opposite edge.
+#[derive(Debug, Clone)]
+pub struct AccessRule {
+    offsets: Vec<(i32, i32)>,
+    pub threshold: usize,
+    pub wrap: bool,
+}
+
+impl Default for AccessRule {
+    /// The puzzle's rule: 8 directions, fewer than 4 neighboring rolls, no wrapping
+    fn default() -> Self {
+        AccessRule::new(Neighborhood::Moore, 1, 4, false)
+    }
+}
+
+impl AccessRule {
+    /// Panics if `radius` is outside 1..=MAX_RADIUS; `from_args` checks this
+    /// before getting here.
+    pub fn new(neighborhood: Neighborhood, radius: i32, threshold: usize, wrap: bool) -> Self {
+        assert!((1..=MAX_RADIUS).contains(&radius), "radius {} outside 1..={}", radius, MAX_RADIUS);
+        AccessRule {
+            offsets: offsets(&neighborhood, radius),
+            threshold,
+            wrap,
+        }
+    }
+
+    /// The (row, col) offsets that make up the neighborhood
+    pub fn offsets(&self) -> &[(i32, i32)] {
+        &self.offsets
+    }
+
+    /// Whether a roll with this many neighboring rolls can be reached
+    pub fn is_accessible(&self, neighbor_rolls: usize) -> bool {
+        neighbor_rolls < self.threshold
+    }
+
+    /// Iterate over the cells whose rolls count towards the given position.
+    ///
+    /// On a wrapped grid smaller than the neighborhood the same cell can be
+    /// reached through several offsets; it is then yielded once per offset.
+    pub fn neighbors(&self, rows: usize, cols: usize, row: usize, col: usize) -> impl Iterator<Item = (usize, usize)> + '_ {
+        self.offsets
+            .iter()
+            .filter_map(move |&(dr, dc)| self.step(rows, cols, row, col, dr, dc))
+    }
+
+    /// Iterate over the cells that count the given position as a neighbor.
+    ///
+    /// This is `neighbors` with every offset negated: the two are the same for
+    /// the symmetric Moore and von Neumann shapes, but not for custom masks.
+    pub fn dependents(&self, rows: usize, cols: usize, row: usize, col: usize) -> impl Iterator<Item = (usize, usize)> + '_ {
+        self.offsets
+            .iter()
+            .filter_map(move |&(dr, dc)| self.step(rows, cols, row, col, -dr, -dc))
+    }
+
+    /// Move from (row, col) by (dr, dc), wrapping or clipping at the edges
+    fn step(&self, rows: usize, cols: usize, row: usize, col: usize, dr: i32, dc: i32) -> Option<(usize, usize)> {
+        let new_row = row as i64 + dr as i64;
+        let new_col = col as i64 + dc as i64;
+
+        if self.wrap {
+            if rows == 0 || cols == 0 {
+                return None;
+            }
+            Some((new_row.rem_euclid(rows as i64) as usize, new_col.rem_euclid(cols as i64) as usize))
+        } else if new_row >= 0 && new_row < rows as i64 && new_col >= 0 && new_col < cols as i64 {
+            Some((new_row as usize, new_col as usize))
+        } else {
+            None
+        }
+    }
+
+    /// Build a rule from command line flags, starting from the puzzle's rule.
+    ///
+    /// Flags: `--neighborhood moore|von-neumann`, `--radius N` (1 to `MAX_RADIUS`),
+    /// `--offsets "dr,dc;dr,dc;..."`, `--threshold N` and `--wrap`.
+    /// Anything else is handed back so the caller can handle its own flags.
+    pub fn from_args(args: impl IntoIterator<Item = String>) -> Result<(Self, Vec<String>), String> {
+        let mut neighborhood = Neighborhood::Moore;
+        let mut radius = 1;
+        let mut threshold = 4;
+        let mut wrap = false;
+        let mut rest = Vec::new();
+
+        let mut args = args.into_iter();
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "--neighborhood" => {
+                    neighborhood = match value(&mut args, &arg)?.as_str() {
+                        "moore" => Neighborhood::Moore,
+                        "von-neumann" => Neighborhood::VonNeumann,
+                        other => return Err(format!("unknown neighborhood '{}' (expected moore or von-neumann)", other)),
+                    };
+                }
+                "--radius" => {
+                    let s = value(&mut args, &arg)?;
+                    radius = s
+                        .parse()
+                        .ok()
+                        .filter(|r| (1..=MAX_RADIUS).contains(r))
+                        .ok_or_else(|| format!("--radius expects an integer from 1 to {}, got '{}'", MAX_RADIUS, s))?;
+                }
+                "--offsets" => neighborhood = Neighborhood::Custom(parse_offsets(&value(&mut args, &arg)?)?),
+                "--threshold" => threshold = number(&value(&mut args, &arg)?, &arg)?,
+                "--wrap" => wrap = true,
+                _ => rest.push(arg),
+            }
+        }
+
+        Ok((AccessRule::new(neighborhood, radius, threshold as usize, wrap), rest))
+    }
+}
+
+/// Expand a neighborhood shape into its list of offsets, excluding (0, 0)
+fn offsets(neighborhood: &Neighborhood, radius: i32) -> Vec<(i32, i32)> {
+    match neighborhood {
+        Neighborhood::Custom(offsets) => offsets.clone(),
+        Neighborhood::Moore | Neighborhood::VonNeumann => {
+            let mut offsets = Vec::new();
+            for dr in -radius..=radius {
+                for dc in -radius..=radius {
+                    let inside = match neighborhood {
+                        Neighborhood::VonNeumann => dr.abs() + dc.abs() <= radius,
+                        _ => true,
+                    };
+                    if inside && (dr, dc) != (0, 0) {
+                        offsets.push((dr, dc));
+                    }
+                }
+            }
+            offsets
+        }
+    }
+}
+
+/// Parse "dr,dc;dr,dc;..." into a list of offsets
+fn parse_offsets(s: &str) -> Result<Vec<(i32, i32)>, String> {
+    let mut offsets = Vec::new();
+
+    for pair in s.split(';').map(str::trim).filter(|p| !p.is_empty()) {
+        let (dr, dc) = pair
+            .split_once(',')
+            .ok_or_else(|| format!("offset '{}' should look like dr,dc", pair))?;
+        let dr: i32 = dr.trim().parse().map_err(|_| format!("bad row offset in '{}'", pair))?;
+        let dc: i32 = dc.trim().parse().map_err(|_| format!("bad column offset in '{}'", pair))?;
+
+        if (dr, dc) == (0, 0) {
+            return Err("offset 0,0 would make a roll its own neighbor".to_string());
+        }
+        offsets.push((dr, dc));
+    }
+
+    if offsets.is_empty() {
+        return Err("--offsets needs at least one dr,dc pair".to_string());
+    }
+    Ok(offsets)
+}
+
+fn value(args: &mut impl Iterator<Item = String>, flag: &str) -> Result<String, String> {
+    args.next().ok_or_else(|| format!("{} needs a value", flag))
+}
+
+fn number(s: &str, flag: &str) -> Result<u32, String> {
+    s.parse().map_err(|_| format!("{} expects a non-negative integer, got '{}'", flag, s))
+}
@@ -0,0 +1,64 @@
+use crate::database::FreshRange;
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
+
+/// Which fresh ranges each available ingredient falls into
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Attribution {
+    /// For each ingredient, in input order: its ID and the line numbers of
+    /// every range covering it (ascending; empty if it isn't fresh)
+    pub ingredients: Vec<(u64, Vec<usize>)>,
+    /// For each range, in input order: how many ingredients fall into it
+    pub hits: Vec<usize>,
+}
+
+impl Attribution {
+    /// Ranges no ingredient falls into
+    pub fn unused<'a>(&'a self, ranges: &'a [FreshRange]) -> impl Iterator<Item = &'a FreshRange> + 'a {
+        ranges.iter().zip(&self.hits).filter(|&(_, &hits)| hits == 0).map(|(range, _)| range)
+    }
+}
+
+/// Match every ingredient against every range that covers it.
+///
+/// Sweep line over the IDs in ascending order: ranges join the active set once
+/// their start is reached and leave it once their end is passed, so each
+/// ingredient only looks at the ranges that actually cover it.
+pub fn attribute(ranges: &[FreshRange], ingredients: &[u64]) -> Attribution {
+    let mut by_start: Vec<usize> = (0..ranges.len()).collect();
+    by_start.sort_by_key(|&i| ranges[i].start);
+    let mut by_id: Vec<usize> = (0..ingredients.len()).collect();
+    by_id.sort_by_key(|&i| ingredients[i]);
+
+    let mut covering = vec![Vec::new(); ingredients.len()];
+    let mut hits = vec![0; ranges.len()];
+
+    // Active ranges, smallest end on top
+    let mut active: BinaryHeap<Reverse<(u64, usize)>> = BinaryHeap::new();
+    let mut next = 0;
+
+    for i in by_id {
+        let id = ingredients[i];
+
+        while next < by_start.len() && ranges[by_start[next]].start <= id {
+            let r = by_start[next];
+            active.push(Reverse((ranges[r].end, r)));
+            next += 1;
+        }
+        while active.peek().is_some_and(|Reverse((end, _))| *end < id) {
+            active.pop();
+        }
+
+        let mut lines: Vec<usize> = active.iter().map(|Reverse((_, r))| ranges[*r].line).collect();
+        lines.sort_unstable();
+        for Reverse((_, r)) in &active {
+            hits[*r] += 1;
+        }
+        covering[i] = lines;
+    }
+
+    Attribution {
+        ingredients: ingredients.iter().copied().zip(covering).collect(),
+        hits,
+    }
+}
@@ -0,0 +1,65 @@
+use crate::Validity;
+use sections::Section;
+
+/// One line from the fresh ranges section of the database: "start-end",
+/// optionally followed by a validity window such as "2025-01-01..2025-03-31"
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FreshRange {
+    /// 1-based line number in the input file
+    pub line: usize,
+    pub start: u64,
+    pub end: u64,
+    /// Dates the range applies on; unbounded when the line has no window
+    pub valid: Validity,
+}
+
+impl FreshRange {
+    /// Whether `id` lies within start..=end
+    pub fn contains(&self, id: u64) -> bool {
+        self.start <= id && id <= self.end
+    }
+}
+
+/// Parse the fresh ranges section, keeping each range's line number so
+/// errors and reports can point back at the input
+pub fn parse_ranges(section: &Section) -> Result<Vec<FreshRange>, String> {
+    section
+        .lines
+        .iter()
+        .enumerate()
+        .map(|(i, line)| {
+            let line_no = section.first_line + i;
+            let mut fields = line.split_whitespace();
+            let (start, end) = fields
+                .next()
+                .and_then(|range| range.split_once('-'))
+                .ok_or_else(|| format!("line {}: expected start-end, got '{}'", line_no, line))?;
+            let parse = |s: &str| {
+                s.parse::<u64>()
+                    .map_err(|_| format!("line {}: '{}' is not an ingredient ID", line_no, s))
+            };
+            let valid = match fields.next() {
+                Some(window) => window.parse().map_err(|e| format!("line {}: {}", line_no, e))?,
+                None => Validity::default(),
+            };
+            if let Some(extra) = fields.next() {
+                return Err(format!("line {}: unexpected '{}' after the validity window", line_no, extra));
+            }
+            Ok(FreshRange { line: line_no, start: parse(start)?, end: parse(end)?, valid })
+        })
+        .collect()
+}
+
+/// Parse the available ingredient IDs section, one ID per line
+pub fn parse_ingredients(section: &Section) -> Result<Vec<u64>, String> {
+    section
+        .lines
+        .iter()
+        .enumerate()
+        .map(|(i, line)| {
+            line.trim()
+                .parse()
+                .map_err(|_| format!("line {}: '{}' is not an ingredient ID", section.first_line + i, line))
+        })
+        .collect()
+}
@@ -0,0 +1,138 @@
+use std::fmt;
+use std::str::FromStr;
+
+/// A calendar date, ordered chronologically
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Date {
+    pub year: i32,
+    pub month: u32,
+    pub day: u32,
+}
+
+impl Date {
+    pub fn new(year: i32, month: u32, day: u32) -> Option<Self> {
+        if (1..=12).contains(&month) && day >= 1 && day <= days_in_month(year, month) {
+            Some(Date { year, month, day })
+        } else {
+            None
+        }
+    }
+
+    /// The following calendar day
+    pub fn next_day(self) -> Self {
+        if self.day < days_in_month(self.year, self.month) {
+            Date { day: self.day + 1, ..self }
+        } else if self.month < 12 {
+            Date { month: self.month + 1, day: 1, ..self }
+        } else {
+            Date { year: self.year + 1, month: 1, day: 1 }
+        }
+    }
+}
+
+fn days_in_month(year: i32, month: u32) -> u32 {
+    match month {
+        4 | 6 | 9 | 11 => 30,
+        2 if year % 4 == 0 && (year % 100 != 0 || year % 400 == 0) => 29,
+        2 => 28,
+        _ => 31,
+    }
+}
+
+impl FromStr for Date {
+    type Err = String;
+
+    /// Parse an ISO date such as "2025-12-05"
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let bad = || format!("'{}' is not a date (expected YYYY-MM-DD)", s);
+        let mut parts = s.trim().splitn(3, '-');
+        let mut next = || parts.next().and_then(|p| p.parse::<u32>().ok()).ok_or_else(bad);
+        let (year, month, day) = (next()?, next()?, next()?);
+        Date::new(year as i32, month, day).ok_or_else(bad)
+    }
+}
+
+impl fmt::Display for Date {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:04}-{:02}-{:02}", self.year, self.month, self.day)
+    }
+}
+
+/// The dates on which a fresh range applies, inclusive at both ends.
+/// A missing bound means the range has no start date or never expires.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Validity {
+    pub from: Option<Date>,
+    pub until: Option<Date>,
+}
+
+impl Validity {
+    /// Whether the range applies on `date`
+    pub fn contains(&self, date: Date) -> bool {
+        self.from.is_none_or(|from| from <= date) && self.until.is_none_or(|until| date <= until)
+    }
+
+    /// Whether every date in `other` is also in this window
+    pub fn covers(&self, other: &Validity) -> bool {
+        let from = match (self.from, other.from) {
+            (None, _) => true,
+            (Some(_), None) => false,
+            (Some(a), Some(b)) => a <= b,
+        };
+        let until = match (self.until, other.until) {
+            (None, _) => true,
+            (Some(_), None) => false,
+            (Some(a), Some(b)) => a >= b,
+        };
+        from && until
+    }
+
+    /// Whether the two windows share at least one date
+    pub fn overlaps(&self, other: &Validity) -> bool {
+        let starts_in_time = |from: Option<Date>, until: Option<Date>| match (from, until) {
+            (Some(from), Some(until)) => from <= until,
+            _ => true,
+        };
+        starts_in_time(self.from, other.until) && starts_in_time(other.from, self.until)
+    }
+
+    /// Whether the window has neither a start nor an end date
+    pub fn is_unbounded(&self) -> bool {
+        self.from.is_none() && self.until.is_none()
+    }
+}
+
+impl fmt::Display for Validity {
+    /// The same "FROM..UNTIL" form the parser reads, empty on a missing side
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Some(from) = self.from {
+            write!(f, "{}", from)?;
+        }
+        write!(f, "..")?;
+        if let Some(until) = self.until {
+            write!(f, "{}", until)?;
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for Validity {
+    type Err = String;
+
+    /// Parse "FROM..UNTIL", where either side may be left empty
+    /// ("2025-01-01..", "..2025-03-31")
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (from, until) = s
+            .split_once("..")
+            .ok_or_else(|| format!("'{}' is not a validity window (expected FROM..UNTIL)", s))?;
+        let bound = |d: &str| if d.is_empty() { Ok(None) } else { d.parse().map(Some) };
+        let validity = Validity { from: bound(from)?, until: bound(until)? };
+
+        if let (Some(from), Some(until)) = (validity.from, validity.until)
+            && from > until
+        {
+            return Err(format!("validity window '{}' ends before it starts", s));
+        }
+        Ok(validity)
+    }
+}
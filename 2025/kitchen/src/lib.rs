@@ -0,0 +1,24 @@
+//! Advent of Code 2025 - Day 5: Cafeteria
+//!
+//! Shared pieces of the ingredient database solvers.
+
+pub mod attribution;
+pub mod database;
+pub mod date;
+pub mod lint;
+pub mod range_set;
+pub mod timeline;
+
+pub use date::{Date, Validity};
+pub use range_set::RangeSet;
+
+use std::{fmt, process};
+
+/// Print the error and stop, for the binaries; every failure they report is
+/// a bad argument or a problem with the input file
+pub fn or_exit<T, E: fmt::Display>(result: Result<T, E>) -> T {
+    result.unwrap_or_else(|e| {
+        eprintln!("{}", e);
+        process::exit(1);
+    })
+}
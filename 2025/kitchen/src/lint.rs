@@ -0,0 +1,123 @@
+use crate::database::FreshRange;
+use crate::{RangeSet, Validity};
+use std::cmp::Reverse;
+
+/// Everything the merge step would otherwise absorb without a word
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Lint {
+    /// Ranges with start > end; they contain no IDs
+    pub inverted: Vec<FreshRange>,
+    /// (inner, outer): `inner` adds nothing because `outer` already covers
+    /// its IDs on every date `inner` is valid
+    pub contained: Vec<(FreshRange, FreshRange)>,
+    /// Pairs that share some IDs on some date without one making the other
+    /// redundant
+    pub overlapping: Vec<(FreshRange, FreshRange)>,
+    /// Pairs valid on a common date where one ends exactly one before the
+    /// other starts
+    pub adjacent: Vec<(FreshRange, FreshRange)>,
+    /// A shorter list giving exactly the same fresh IDs on every date: the
+    /// ranges of each validity window merged, minus merged ranges that a wider
+    /// window already covers. Unbounded ranges come first.
+    pub rewritten: Vec<(Validity, RangeSet<u64>)>,
+}
+
+impl Lint {
+    /// Number of ranges in the rewritten list
+    pub fn rewritten_count(&self) -> usize {
+        self.rewritten.iter().map(|(_, set)| set.range_count()).sum()
+    }
+}
+
+/// Check a range list for redundancy.
+///
+/// Ranges are visited in order of start (longest first on ties, then widest
+/// window), keeping the earlier ranges that still reach the current start, so
+/// each range is only compared with ranges it actually touches. Two ranges
+/// whose windows never share a date can't interact and are not reported.
+pub fn lint(ranges: &[FreshRange]) -> Lint {
+    let inverted: Vec<FreshRange> = ranges.iter().filter(|r| r.start > r.end).copied().collect();
+
+    let mut sorted: Vec<FreshRange> = ranges.iter().filter(|r| r.start <= r.end).copied().collect();
+    // A window that covers another sorts before it (earlier start, later end)
+    sorted.sort_by_key(|r| {
+        (r.start, Reverse(r.end), r.valid.from, Reverse((r.valid.until.is_none(), r.valid.until)), r.line)
+    });
+
+    let mut contained = Vec::new();
+    let mut overlapping = Vec::new();
+    let mut adjacent = Vec::new();
+    let mut active: Vec<FreshRange> = Vec::new();
+
+    for &range in &sorted {
+        // Drop ranges ending more than one before this start; they can't touch it
+        active.retain(|earlier| earlier.end.saturating_add(1) >= range.start);
+
+        let mut inside = None;
+        for &earlier in active.iter().filter(|earlier| earlier.valid.overlaps(&range.valid)) {
+            if earlier.end >= range.end && earlier.valid.covers(&range.valid) {
+                inside.get_or_insert(earlier);
+            } else if earlier.end >= range.start {
+                overlapping.push(ordered(earlier, range));
+            } else {
+                adjacent.push(ordered(earlier, range));
+            }
+        }
+
+        if let Some(outer) = inside {
+            contained.push((range, outer));
+        }
+        active.push(range);
+    }
+
+    contained.sort_by_key(|(inner, _)| inner.line);
+    overlapping.sort_by_key(|(a, b)| (a.line, b.line));
+    adjacent.sort_by_key(|(a, b)| (a.line, b.line));
+
+    Lint {
+        inverted,
+        contained,
+        overlapping,
+        adjacent,
+        rewritten: rewrite(&sorted),
+    }
+}
+
+/// Merge the ranges of each distinct window, then drop merged ranges that the
+/// ranges of strictly wider windows already cover.
+///
+/// Only whole merged ranges are dropped, never trimmed, so the list can't grow.
+/// A wider window's set is only ever reduced by what an even wider one covers,
+/// so whatever it drops is still covered on the narrower window's dates.
+fn rewrite(ranges: &[FreshRange]) -> Vec<(Validity, RangeSet<u64>)> {
+    let mut windows: Vec<(Validity, RangeSet<u64>)> = Vec::new();
+    for range in ranges {
+        match windows.iter_mut().find(|(valid, _)| *valid == range.valid) {
+            Some((_, set)) => set.insert(range.start, range.end),
+            None => windows.push((range.valid, [(range.start, range.end)].into_iter().collect())),
+        }
+    }
+    windows.sort_by_key(|(valid, _)| (!valid.is_unbounded(), valid.from, valid.until));
+
+    windows
+        .iter()
+        .map(|(valid, set)| {
+            let wider: RangeSet<u64> = windows
+                .iter()
+                .filter(|(other, _)| other != valid && other.covers(valid))
+                .flat_map(|(_, set)| set.iter())
+                .collect();
+            let kept: RangeSet<u64> = set
+                .iter()
+                .filter(|&(start, end)| !RangeSet::from_iter([(start, end)]).difference(&wider).is_empty())
+                .collect();
+            (*valid, kept)
+        })
+        .filter(|(_, set)| !set.is_empty())
+        .collect()
+}
+
+/// Put a pair in input order
+fn ordered(a: FreshRange, b: FreshRange) -> (FreshRange, FreshRange) {
+    if a.line <= b.line { (a, b) } else { (b, a) }
+}
@@ -1,4 +1,7 @@
-use std::fs;
+use kitchen::database::{self, FreshRange};
+use kitchen::{attribution, or_exit, timeline, Date, RangeSet};
+use sections::Sections;
+use std::{env, fs, process};
 
 /// Advent of Code 2025 - Day 5: Cafeteria
 ///
@@ -12,42 +15,115 @@ use std::fs;
 /// Output: Count of available ingredient IDs that fall within any fresh range
 ///
 /// Key insight: Ranges are inclusive and can overlap - an ingredient is fresh
-/// if it falls into ANY of the given ranges.
-
+/// if it falls into ANY of the given ranges. Merging them into a `RangeSet`
+/// first turns each lookup into a binary search instead of a scan of every range.
+///
+/// A range line may end with a validity window ("3-5 2025-01-01..2025-03-31",
+/// either side optional). Windows are ignored unless a date is asked for.
+///
+/// Usage: kitchen [--report] [--as-of YYYY-MM-DD] [--timeline]
+///
+/// `--report` also lists, for every ingredient, the line numbers of the ranges
+/// covering it, how many ingredients each range caught, and the ranges nothing
+/// fell into, so stale entries can be pruned from the database.
+///
+/// `--as-of` only uses the ranges valid on that date; `--timeline` prints the
+/// fresh ingredient count for every date on which it can change.
 fn main() {
+    let mut show_report = false;
+    let mut as_of: Option<Date> = None;
+    let mut show_timeline = false;
+    let mut args = env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--report" => show_report = true,
+            "--as-of" => as_of = Some(or_exit(args.next().unwrap_or_default().parse())),
+            "--timeline" => show_timeline = true,
+            _ => {
+                eprintln!("unknown argument '{}'", arg);
+                process::exit(2);
+            }
+        }
+    }
+
     // Read the puzzle input file containing the ingredient database
     let input = fs::read_to_string("input.txt").expect("Failed to read input file");
     
     // Split input into ranges and ingredient IDs sections (separated by blank line)
-    let parts: Vec<&str> = input.split("\n\n").collect();
-    let ranges_section = parts[0];
-    let ingredients_section = parts[1];
-    
-    // Parse the fresh ingredient ID ranges into (start, end) tuples
+    let sections = Sections::parse(&input).with_names(&["ranges", "ingredients"]);
+
+    // Parse the fresh ingredient ID ranges, keeping their line numbers for the report
     // Each line is formatted as "start-end" where both bounds are inclusive
-    let ranges: Vec<(u64, u64)> = ranges_section
-        .lines()
-        .map(|line| {
-            let mut parts = line.split('-');
-            let start: u64 = parts.next().unwrap().parse().unwrap();
-            let end: u64 = parts.next().unwrap().parse().unwrap();
-            (start, end)
-        })
-        .collect();
-    
+    let mut fresh_ranges = or_exit(database::parse_ranges(or_exit(sections.get("ranges"))));
+
     // Parse available ingredient IDs (one per line after the blank line)
-    let ingredients: Vec<u64> = ingredients_section
-        .lines()
-        .filter(|line| !line.is_empty())
-        .map(|line| line.parse().unwrap())
-        .collect();
+    let ingredients = or_exit(database::parse_ingredients(or_exit(sections.get("ingredients"))));
+
+    if show_timeline {
+        print_timeline(&fresh_ranges, &ingredients);
+    }
+
+    // Answering for a specific date means forgetting every range not valid on it
+    if let Some(date) = as_of {
+        fresh_ranges.retain(|r| r.valid.contains(date));
+    }
+
+    let ranges: RangeSet<u64> = fresh_ranges.iter().map(|r| (r.start, r.end)).collect();
     
     // Count fresh ingredients: those that fall into at least one range
     // An ingredient ID is fresh if: start <= id <= end for any range
     let fresh_count = ingredients
         .iter()
-        .filter(|&&id| ranges.iter().any(|&(start, end)| id >= start && id <= end))
+        .filter(|&&id| ranges.contains(id))
         .count();
 
+    if show_report {
+        print_report(&fresh_ranges, &ingredients);
+    }
+
     println!("Number of fresh ingredient IDs: {}", fresh_count);
 }
+
+/// Print how many ingredients are fresh on every date the answer can change
+fn print_timeline(ranges: &[FreshRange], ingredients: &[u64]) {
+    println!("Fresh ingredients by date:");
+    for (date, fresh) in timeline::timeline(ranges) {
+        let count = ingredients.iter().filter(|&&id| fresh.contains(id)).count();
+        match date {
+            Some(date) => println!("  from {}  {:>6}", date, count),
+            None => println!("  from the start   {:>6}", count),
+        }
+    }
+    println!();
+}
+
+/// Print which ranges cover each ingredient, per-range hit counts and unused ranges
+fn print_report(ranges: &[FreshRange], ingredients: &[u64]) {
+    let report = attribution::attribute(ranges, ingredients);
+
+    println!("Ingredients:");
+    for (id, lines) in &report.ingredients {
+        if lines.is_empty() {
+            println!("  {:>16}  spoiled", id);
+        } else {
+            let lines: Vec<String> = lines.iter().map(|l| l.to_string()).collect();
+            let label = if lines.len() == 1 { "line" } else { "lines" };
+            println!("  {:>16}  fresh ({} {})", id, label, lines.join(", "));
+        }
+    }
+
+    println!();
+    println!("Hits per range:");
+    for (range, hits) in ranges.iter().zip(&report.hits) {
+        println!("  line {:>4}  {:>16}-{:<16}  {:>4}", range.line, range.start, range.end, hits);
+    }
+
+    let unused: Vec<String> = report.unused(ranges).map(|r| r.line.to_string()).collect();
+    println!();
+    if unused.is_empty() {
+        println!("Every range covers at least one ingredient");
+    } else {
+        println!("Ranges no ingredient falls into ({}): lines {}", unused.len(), unused.join(", "));
+    }
+    println!();
+}
@@ -1,4 +1,7 @@
-use std::fs;
+use kitchen::database::{self, FreshRange};
+use kitchen::{lint, or_exit, timeline, Date, RangeSet};
+use sections::Sections;
+use std::{env, fs, process};
 
 /// Advent of Code 2025 - Day 5: Cafeteria (Part 2)
 ///
@@ -12,55 +15,120 @@ use std::fs;
 ///
 /// Key insight: Ranges can overlap, so we need to merge overlapping ranges
 /// to avoid counting the same ID twice. We sort ranges by start, then merge
-/// any that overlap or are adjacent.
-
+/// any that overlap or are adjacent. Inverted ranges (start > end) hold no IDs
+/// and are skipped rather than underflowing in `end - start + 1`.
+///
+/// Usage: kitchen_part2 [--lint] [--as-of YYYY-MM-DD] [--timeline]
+///
+/// `--lint` reports what the merge absorbs: inverted ranges, ranges contained
+/// in another, overlapping and adjacent pairs, and a shorter equivalent list.
+/// Ranges only interact when their validity windows share a date, and the
+/// rewritten list keeps each range's window.
+///
+/// `--as-of` only counts the ranges valid on that date (see the validity
+/// windows in `kitchen`); `--timeline` prints the total for every date on
+/// which it can change, merging the ranges valid on each date separately.
 fn main() {
+    let mut show_lint = false;
+    let mut as_of: Option<Date> = None;
+    let mut show_timeline = false;
+    let mut args = env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--lint" => show_lint = true,
+            "--as-of" => as_of = Some(or_exit(args.next().unwrap_or_default().parse())),
+            "--timeline" => show_timeline = true,
+            _ => {
+                eprintln!("unknown argument '{}'", arg);
+                process::exit(2);
+            }
+        }
+    }
+
     // Read the puzzle input file containing the ingredient database
     let input = fs::read_to_string("input.txt").expect("Failed to read input file");
     
     // Only need the ranges section (before the blank line)
-    let ranges_section = input.split("\n\n").next().unwrap();
-    
-    // Parse the fresh ingredient ID ranges into (start, end) tuples
-    let mut ranges: Vec<(u64, u64)> = ranges_section
-        .lines()
-        .map(|line| {
-            let mut parts = line.split('-');
-            let start: u64 = parts.next().unwrap().parse().unwrap();
-            let end: u64 = parts.next().unwrap().parse().unwrap();
-            (start, end)
-        })
-        .collect();
-    
-    // Sort ranges by start value to prepare for merging
-    ranges.sort_by_key(|&(start, _)| start);
+    let sections = Sections::parse(&input).with_names(&["ranges", "ingredients"]);
     
-    // Merge overlapping and adjacent ranges
-    // This ensures we don't count the same ID twice
-    let mut merged: Vec<(u64, u64)> = Vec::new();
-    
-    for (start, end) in ranges {
-        if let Some(last) = merged.last_mut() {
-            // Check if current range overlaps or is adjacent to the last merged range
-            // Use start <= last.1 + 1 to handle adjacent ranges (e.g., 3-5 and 6-8)
-            if start <= last.1 + 1 {
-                // Extend the last range if current range extends further
-                last.1 = last.1.max(end);
-            } else {
-                // No overlap, add as a new range
-                merged.push((start, end));
+    // Parse the fresh ingredient ID ranges, keeping their line numbers for the lint
+    let mut ranges = or_exit(database::parse_ranges(or_exit(sections.get("ranges"))));
+
+    if show_timeline {
+        println!("Fresh ingredient IDs by date:");
+        for (date, fresh) in timeline::timeline(&ranges) {
+            match date {
+                Some(date) => println!("  from {}  {:>16}", date, fresh.len()),
+                None => println!("  from the start   {:>16}", fresh.len()),
             }
-        } else {
-            // First range
-            merged.push((start, end));
         }
+        println!();
     }
-    
+
+    // Answering for a specific date means forgetting every range not valid on it
+    if let Some(date) = as_of {
+        ranges.retain(|r| r.valid.contains(date));
+    }
+
+    // Collecting into a RangeSet sorts them by start and merges overlapping
+    // and adjacent ranges (e.g., 3-5 and 6-8), so no ID is counted twice
+    let fresh: RangeSet<u64> = ranges.iter().map(|r| (r.start, r.end)).collect();
+
+    if show_lint {
+        print_lint(&ranges);
+    }
+
     // Count total fresh IDs: sum of (end - start + 1) for each merged range
-    let total_fresh: u64 = merged
-        .iter()
-        .map(|&(start, end)| end - start + 1)
-        .sum();
+    let total_fresh = fresh.len();
 
     println!("Total fresh ingredient IDs: {}", total_fresh);
 }
+
+fn describe(range: &FreshRange) -> String {
+    if range.valid.is_unbounded() {
+        format!("line {} ({}-{})", range.line, range.start, range.end)
+    } else {
+        format!("line {} ({}-{} {})", range.line, range.start, range.end, range.valid)
+    }
+}
+
+/// Print every redundancy the merge would absorb, then the minimal range list
+fn print_lint(ranges: &[FreshRange]) {
+    let report = lint::lint(ranges);
+
+    println!("Inverted ranges, start > end ({}):", report.inverted.len());
+    for range in &report.inverted {
+        println!("  {}", describe(range));
+    }
+
+    println!("Ranges contained in another ({}):", report.contained.len());
+    for (inner, outer) in &report.contained {
+        println!("  {} is inside {}", describe(inner), describe(outer));
+    }
+
+    println!("Overlapping pairs ({}):", report.overlapping.len());
+    for (a, b) in &report.overlapping {
+        println!("  {} overlaps {}", describe(a), describe(b));
+    }
+
+    println!("Adjacent pairs ({}):", report.adjacent.len());
+    for (a, b) in &report.adjacent {
+        println!("  {} touches {}", describe(a), describe(b));
+    }
+
+    println!(
+        "Equivalent range list ({} ranges, was {}):",
+        report.rewritten_count(),
+        ranges.len()
+    );
+    for (valid, set) in &report.rewritten {
+        for (start, end) in set.iter() {
+            if valid.is_unbounded() {
+                println!("{}-{}", start, end);
+            } else {
+                println!("{}-{} {}", start, end, valid);
+            }
+        }
+    }
+    println!();
+}
@@ -0,0 +1,204 @@
+use std::fmt::Debug;
+
+/// Integer types a `RangeSet` can hold.
+///
+/// Ranges are inclusive at both ends, so merging needs to know whether two
+/// ranges touch (`end + 1 == start`) without overflowing at the type's maximum.
+pub trait RangeBound: Copy + Ord + Debug {
+    /// `self + 1`, or `None` at the maximum value
+    fn successor(self) -> Option<Self>;
+    /// `self - 1`, or `None` at the minimum value
+    fn predecessor(self) -> Option<Self>;
+    /// Number of values in `start..=end`; wide enough for the full type range
+    fn span(start: Self, end: Self) -> u128;
+}
+
+macro_rules! impl_range_bound {
+    ($($t:ty),*) => {
+        $(
+            impl RangeBound for $t {
+                fn successor(self) -> Option<Self> {
+                    self.checked_add(1)
+                }
+
+                fn predecessor(self) -> Option<Self> {
+                    self.checked_sub(1)
+                }
+
+                fn span(start: Self, end: Self) -> u128 {
+                    (end - start) as u128 + 1
+                }
+            }
+        )*
+    };
+}
+
+impl_range_bound!(u8, u16, u32, u64, usize);
+
+/// A set of integers stored as sorted, disjoint, non-adjacent inclusive ranges.
+///
+/// Overlapping and adjacent ranges are merged as they are added (e.g. 3-5 and
+/// 6-8 become 3-8), so membership is a binary search over the merged ranges
+/// and the cardinality is a sum of their lengths.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RangeSet<T> {
+    ranges: Vec<(T, T)>,
+}
+
+impl<T: RangeBound> Default for RangeSet<T> {
+    fn default() -> Self {
+        RangeSet { ranges: Vec::new() }
+    }
+}
+
+impl<T: RangeBound> RangeSet<T> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add every value in `start..=end`. An inverted range (start > end) is empty
+    /// and leaves the set unchanged.
+    pub fn insert(&mut self, start: T, end: T) {
+        if start > end {
+            return;
+        }
+
+        // Ranges ending before start - 1 can't touch the new range
+        let first = self
+            .ranges
+            .partition_point(|&(_, e)| e.successor().is_some_and(|next| next < start));
+        // Ranges starting after end + 1 can't touch it either
+        let last = match end.successor() {
+            Some(next) => self.ranges.partition_point(|&(s, _)| s <= next),
+            None => self.ranges.len(),
+        };
+
+        let mut merged = (start, end);
+        if first < last {
+            merged.0 = merged.0.min(self.ranges[first].0);
+            merged.1 = merged.1.max(self.ranges[last - 1].1);
+        }
+        self.ranges.splice(first..last, [merged]);
+    }
+
+    /// Whether `value` falls inside any range
+    pub fn contains(&self, value: T) -> bool {
+        // First range that ends at or after value; it's the only candidate
+        let i = self.ranges.partition_point(|&(_, end)| end < value);
+        self.ranges.get(i).is_some_and(|&(start, _)| start <= value)
+    }
+
+    /// The merged ranges in ascending order
+    pub fn iter(&self) -> impl Iterator<Item = (T, T)> + '_ {
+        self.ranges.iter().copied()
+    }
+
+    /// Number of merged ranges
+    pub fn range_count(&self) -> usize {
+        self.ranges.len()
+    }
+
+    /// Total number of values in the set
+    pub fn len(&self) -> u128 {
+        self.ranges.iter().map(|&(start, end)| T::span(start, end)).sum()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.ranges.is_empty()
+    }
+
+    /// Values in either set
+    pub fn union(&self, other: &Self) -> Self {
+        self.iter().chain(other.iter()).collect()
+    }
+
+    /// Values in both sets
+    pub fn intersection(&self, other: &Self) -> Self {
+        let mut ranges = Vec::new();
+        let (mut i, mut j) = (0, 0);
+
+        // Two-pointer sweep: advance whichever range ends first
+        while i < self.ranges.len() && j < other.ranges.len() {
+            let (a_start, a_end) = self.ranges[i];
+            let (b_start, b_end) = other.ranges[j];
+
+            let start = a_start.max(b_start);
+            let end = a_end.min(b_end);
+            if start <= end {
+                ranges.push((start, end));
+            }
+
+            if a_end < b_end {
+                i += 1;
+            } else {
+                j += 1;
+            }
+        }
+        RangeSet { ranges }
+    }
+
+    /// Values in `self` but not in `other`
+    pub fn difference(&self, other: &Self) -> Self {
+        let mut ranges = Vec::new();
+        let mut j = 0;
+
+        for &(start, end) in &self.ranges {
+            let mut current = Some(start);
+
+            // Skip ranges of `other` that end before this one starts
+            while j < other.ranges.len() && other.ranges[j].1 < start {
+                j += 1;
+            }
+
+            // Cut out every overlapping range of `other`
+            let mut k = j;
+            while let Some(from) = current {
+                let Some(&(cut_start, cut_end)) = other.ranges.get(k).filter(|&&(s, _)| s <= end) else {
+                    break;
+                };
+                if cut_start > from {
+                    // cut_start > from >= T::MIN, so the predecessor exists
+                    ranges.push((from, cut_start.predecessor().unwrap()));
+                }
+                current = if cut_end < end { cut_end.successor() } else { None };
+                k += 1;
+            }
+
+            if let Some(from) = current {
+                ranges.push((from, end));
+            }
+        }
+        RangeSet { ranges }
+    }
+}
+
+impl<T: RangeBound> FromIterator<(T, T)> for RangeSet<T> {
+    /// Sort by start, then merge any range that overlaps or is adjacent to the
+    /// last merged one. Inverted ranges are skipped.
+    fn from_iter<I: IntoIterator<Item = (T, T)>>(iter: I) -> Self {
+        let mut sorted: Vec<(T, T)> = iter.into_iter().filter(|&(start, end)| start <= end).collect();
+        sorted.sort_by_key(|&(start, _)| start);
+
+        let mut ranges: Vec<(T, T)> = Vec::new();
+        for (start, end) in sorted {
+            if let Some(last) = ranges.last_mut() {
+                // start <= last.1 + 1 also catches adjacent ranges (3-5 and 6-8);
+                // at T::MAX nothing can start after the last range
+                if last.1.successor().is_none_or(|next| start <= next) {
+                    last.1 = last.1.max(end);
+                    continue;
+                }
+            }
+            ranges.push((start, end));
+        }
+        RangeSet { ranges }
+    }
+}
+
+impl<T: RangeBound> Extend<(T, T)> for RangeSet<T> {
+    fn extend<I: IntoIterator<Item = (T, T)>>(&mut self, iter: I) {
+        for (start, end) in iter {
+            self.insert(start, end);
+        }
+    }
+}
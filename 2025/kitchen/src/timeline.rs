@@ -0,0 +1,38 @@
+use crate::date::Date;
+use crate::database::FreshRange;
+use crate::RangeSet;
+
+/// The IDs that are fresh on `date`: every range whose validity window covers it
+pub fn fresh_on(ranges: &[FreshRange], date: Date) -> RangeSet<u64> {
+    ranges
+        .iter()
+        .filter(|r| r.valid.contains(date))
+        .map(|r| (r.start, r.end))
+        .collect()
+}
+
+/// The fresh IDs over time, one entry per date on which the set can change.
+///
+/// The first entry starts at `None`, meaning every date before the first
+/// change; each entry then holds until the next one begins. Changes happen
+/// on a range's first valid day and on the day after its last.
+pub fn timeline(ranges: &[FreshRange]) -> Vec<(Option<Date>, RangeSet<u64>)> {
+    let mut changes: Vec<Date> = ranges
+        .iter()
+        .flat_map(|r| [r.valid.from, r.valid.until.map(Date::next_day)])
+        .flatten()
+        .collect();
+    changes.sort();
+    changes.dedup();
+
+    // Before the first change only ranges without a start date can apply
+    let before: RangeSet<u64> = ranges
+        .iter()
+        .filter(|r| r.valid.from.is_none())
+        .map(|r| (r.start, r.end))
+        .collect();
+
+    std::iter::once((None, before))
+        .chain(changes.into_iter().map(|date| (Some(date), fresh_on(ranges, date))))
+        .collect()
+}
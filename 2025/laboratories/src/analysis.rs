@@ -0,0 +1,68 @@
+//! Layout analysis: which parts of a manifold could be taken out.
+//!
+//! A splitter that never splits a beam (at most a horizontal beam passes
+//! through it) and a column no beam occupies do nothing. A splitter that is
+//! hit may still be redundant if removing it leaves both
+//! answers unchanged, e.g. when the straight-through beam is split by another
+//! splitter further down; those are found by removing each hit splitter in
+//! turn and counting again.
+
+use crate::{Exact, Manifold, Rule, SideExits, TimelineError, count_timelines, propagate};
+
+/// What a manifold could do without
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Analysis {
+    /// Splitters that never split a beam
+    pub unreached: Vec<(usize, usize)>,
+    /// Columns with no beam in any row
+    pub dark_columns: Vec<usize>,
+    /// Splitters that are hit, but whose removal changes neither the split
+    /// count nor the timeline total
+    pub redundant: Vec<(usize, usize)>,
+}
+
+/// Analyze the manifold. Timelines are counted exactly, so this only fails
+/// when beams loop (and the timeline total is infinite).
+pub fn analyze(manifold: &Manifold) -> Result<Analysis, TimelineError> {
+    let propagation = propagate(manifold);
+    let split_count = propagation.split_count();
+    let total = count_timelines(manifold, &Exact, SideExits::Dropped)?.total;
+
+    let hit = propagation.splitters_hit();
+    let (reached, unreached): (Vec<_>, Vec<_>) = manifold.splitters().into_iter().partition(|cell| hit.contains(cell));
+
+    let dark_columns = (0..manifold.cols())
+        .filter(|&col| (0..manifold.rows()).all(|row| !propagation.is_lit(row, col)))
+        .collect();
+
+    // Take each reached splitter out of a scratch copy and put it back after
+    let mut scratch = manifold.clone();
+    let removed = blank_cell(manifold);
+    scratch.rules.set(removed, Rule::Empty);
+    let mut redundant = Vec::new();
+    for (row, col) in reached {
+        let original = scratch.cell(row, col);
+        scratch.set_cell(row, col, removed);
+
+        // A removal that makes the beams loop changes the total to infinity
+        let same_splits = propagate(&scratch).split_count() == split_count;
+        if same_splits && count_timelines(&scratch, &Exact, SideExits::Dropped).is_ok_and(|t| t.total == total) {
+            redundant.push((row, col));
+        }
+        scratch.set_cell(row, col, original);
+    }
+
+    Ok(Analysis { unreached, dark_columns, redundant })
+}
+
+/// A character to overwrite removed splitters with: '.' unless `--rule` gave
+/// it another meaning, otherwise one that appears nowhere in the grid
+fn blank_cell(manifold: &Manifold) -> char {
+    if manifold.rules.rule('.') == Rule::Empty {
+        return '.';
+    }
+    let used = |c| (0..manifold.rows()).any(|row| (0..manifold.cols()).any(|col| manifold.cell(row, col) == c));
+    ('\u{E000}'..='\u{F8FF}')
+        .find(|&c| !used(c))
+        .expect("a grid uses fewer characters than the private use area holds")
+}
@@ -0,0 +1,464 @@
+use crate::{Counter, Manifold, Rule};
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt;
+
+/// The way a beam is travelling
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Direction {
+    Down,
+    Up,
+    Left,
+    Right,
+}
+
+impl Direction {
+    pub const ALL: [Direction; 4] = [Direction::Down, Direction::Up, Direction::Left, Direction::Right];
+
+    fn index(self) -> usize {
+        self as usize
+    }
+
+    fn is_vertical(self) -> bool {
+        matches!(self, Direction::Down | Direction::Up)
+    }
+
+    /// The new direction after bouncing off a `/` or `\` mirror
+    fn reflect(self, rule: Rule) -> Direction {
+        match (rule, self) {
+            (Rule::Slash, Direction::Down) | (Rule::Backslash, Direction::Up) => Direction::Left,
+            (Rule::Slash, Direction::Up) | (Rule::Backslash, Direction::Down) => Direction::Right,
+            (Rule::Slash, Direction::Left) | (Rule::Backslash, Direction::Right) => Direction::Down,
+            (Rule::Slash, Direction::Right) | (Rule::Backslash, Direction::Left) => Direction::Up,
+            _ => self,
+        }
+    }
+}
+
+impl fmt::Display for Direction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Direction::Down => write!(f, "down"),
+            Direction::Up => write!(f, "up"),
+            Direction::Left => write!(f, "left"),
+            Direction::Right => write!(f, "right"),
+        }
+    }
+}
+
+/// A beam in a cell whose rule has already been applied, about to move on
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Beam {
+    pub row: usize,
+    pub col: usize,
+    pub direction: Direction,
+}
+
+/// Where a timeline ends
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Outcome {
+    /// Fell off the bottom edge in this column
+    Bottom(usize),
+    /// Left through the top edge in this column
+    Top(usize),
+    /// Left through the left edge in this row
+    Left(usize),
+    /// Left through the right edge in this row
+    Right(usize),
+    /// Stopped by the absorber at (row, col)
+    Absorbed(usize, usize),
+}
+
+impl Outcome {
+    /// Which edge the timeline left through, or "absorbed"
+    pub fn edge(self) -> &'static str {
+        match self {
+            Outcome::Bottom(_) => "bottom",
+            Outcome::Top(_) => "top",
+            Outcome::Left(_) => "left",
+            Outcome::Right(_) => "right",
+            Outcome::Absorbed(..) => "absorbed",
+        }
+    }
+
+    /// The row and column of the exit, where they apply: side exits only
+    /// have a row and top/bottom exits only a column
+    pub fn position(self) -> (Option<usize>, Option<usize>) {
+        match self {
+            Outcome::Bottom(col) | Outcome::Top(col) => (None, Some(col)),
+            Outcome::Left(row) | Outcome::Right(row) => (Some(row), None),
+            Outcome::Absorbed(row, col) => (Some(row), Some(col)),
+        }
+    }
+}
+
+impl fmt::Display for Outcome {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Outcome::Bottom(col) => write!(f, "bottom, column {}", col),
+            Outcome::Top(col) => write!(f, "top, column {}", col),
+            Outcome::Left(row) => write!(f, "left side, row {}", row),
+            Outcome::Right(row) => write!(f, "right side, row {}", row),
+            Outcome::Absorbed(row, col) => write!(f, "absorbed at ({}, {})", row, col),
+        }
+    }
+}
+
+/// Whether timelines that leave through the left or right edge count.
+///
+/// The original Part 2 only follows beams down and drops a splitter branch
+/// that would leave the grid, so by default side exits are left out of the
+/// total; `Counted` includes them.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum SideExits {
+    #[default]
+    Dropped,
+    Counted,
+}
+
+impl SideExits {
+    /// Whether a timeline ending this way counts
+    pub fn counts(self, outcome: Outcome) -> bool {
+        self == SideExits::Counted || !matches!(outcome, Outcome::Left(_) | Outcome::Right(_))
+    }
+}
+
+/// One thing a beam can turn into after a step
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Move {
+    Beam(Beam),
+    End(Outcome),
+}
+
+/// The result of moving a beam one cell
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Step {
+    /// The cell the beam moved into, if it stayed on the grid
+    pub entered: Option<(usize, usize)>,
+    /// The splitter that split the beam, if any
+    pub split: Option<(usize, usize)>,
+    /// What the beam became, left branch before right for a split
+    pub moves: Vec<Move>,
+}
+
+/// The beams every source emits: one per source, moving down
+pub fn initial_beams(manifold: &Manifold) -> Vec<Beam> {
+    manifold
+        .sources()
+        .into_iter()
+        .map(|(row, col)| Beam { row, col, direction: Direction::Down })
+        .collect()
+}
+
+/// The cell one step from (row, col), or the outcome of leaving the grid
+fn neighbor(manifold: &Manifold, row: usize, col: usize, direction: Direction) -> Result<(usize, usize), Outcome> {
+    match direction {
+        Direction::Down if row + 1 < manifold.rows() => Ok((row + 1, col)),
+        Direction::Down => Err(Outcome::Bottom(col)),
+        Direction::Up if row > 0 => Ok((row - 1, col)),
+        Direction::Up => Err(Outcome::Top(col)),
+        Direction::Left if col > 0 => Ok((row, col - 1)),
+        Direction::Left => Err(Outcome::Left(row)),
+        Direction::Right if col + 1 < manifold.cols() => Ok((row, col + 1)),
+        Direction::Right => Err(Outcome::Right(row)),
+    }
+}
+
+/// A beam placed in the cell next to (row, col) without that cell's rule
+/// being applied, as a splitter does with the two halves of a beam
+fn beam_beside(manifold: &Manifold, row: usize, col: usize, side: Direction, direction: Direction) -> Move {
+    match neighbor(manifold, row, col, side) {
+        Ok((row, col)) => Move::Beam(Beam { row, col, direction }),
+        Err(outcome) => Move::End(outcome),
+    }
+}
+
+/// Move a beam into the next cell and apply that cell's rule
+pub fn step(manifold: &Manifold, beam: Beam) -> Step {
+    let (row, col) = match neighbor(manifold, beam.row, beam.col, beam.direction) {
+        Ok(cell) => cell,
+        Err(outcome) => return Step { entered: None, split: None, moves: vec![Move::End(outcome)] },
+    };
+    let direction = beam.direction;
+    let mut split = None;
+
+    let moves = match manifold.rule_at(row, col) {
+        Rule::Splitter if direction.is_vertical() => {
+            split = Some((row, col));
+            vec![
+                beam_beside(manifold, row, col, Direction::Left, direction),
+                beam_beside(manifold, row, col, Direction::Right, direction),
+            ]
+        }
+        rule @ (Rule::Slash | Rule::Backslash) => {
+            vec![Move::Beam(Beam { row, col, direction: direction.reflect(rule) })]
+        }
+        Rule::Absorber => vec![Move::End(Outcome::Absorbed(row, col))],
+        Rule::Empty | Rule::Source | Rule::Splitter => vec![Move::Beam(Beam { row, col, direction })],
+    };
+
+    Step { entered: Some((row, col)), split, moves }
+}
+
+/// Dense index of a beam state, for per-state tables
+pub(crate) fn state_index(manifold: &Manifold, beam: Beam) -> usize {
+    (beam.row * manifold.cols() + beam.col) * Direction::ALL.len() + beam.direction.index()
+}
+
+/// Every beam state and cell reached from the sources (Part 1)
+#[derive(Debug, Clone)]
+pub struct Propagation {
+    cols: usize,
+    /// Per cell, whether a vertical and whether a horizontal beam entered it
+    /// or was emitted into it
+    lit: Vec<(bool, bool)>,
+    /// Split events: a splitter and the direction of the beam it split
+    pub splits: BTreeSet<(usize, usize, Direction)>,
+    /// Every way a beam left the grid or was stopped
+    pub outcomes: BTreeSet<Outcome>,
+}
+
+impl Propagation {
+    /// Number of distinct split events
+    pub fn split_count(&self) -> usize {
+        self.splits.len()
+    }
+
+    pub fn is_lit(&self, row: usize, col: usize) -> bool {
+        let (vertical, horizontal) = self.lit[row * self.cols + col];
+        vertical || horizontal
+    }
+
+    /// Whether a vertical and whether a horizontal beam passed (row, col)
+    pub fn beam_axes(&self, row: usize, col: usize) -> (bool, bool) {
+        self.lit[row * self.cols + col]
+    }
+
+    fn light(&mut self, row: usize, col: usize, direction: Direction) {
+        let axes = &mut self.lit[row * self.cols + col];
+        if direction.is_vertical() {
+            axes.0 = true;
+        } else {
+            axes.1 = true;
+        }
+    }
+
+    /// Splitters that split at least one beam
+    pub fn splitters_hit(&self) -> BTreeSet<(usize, usize)> {
+        self.splits.iter().map(|&(row, col, _)| (row, col)).collect()
+    }
+}
+
+/// Follow every beam from every source. Beams that meet in the same cell
+/// moving the same way merge, so each state is processed once and loops
+/// through mirrors terminate.
+pub fn propagate(manifold: &Manifold) -> Propagation {
+    let mut visited = vec![false; manifold.rows() * manifold.cols() * Direction::ALL.len()];
+    let mut propagation = Propagation {
+        cols: manifold.cols(),
+        lit: vec![(false, false); manifold.rows() * manifold.cols()],
+        splits: BTreeSet::new(),
+        outcomes: BTreeSet::new(),
+    };
+
+    let mut pending = initial_beams(manifold);
+    while let Some(beam) = pending.pop() {
+        let index = state_index(manifold, beam);
+        if visited[index] {
+            continue;
+        }
+        visited[index] = true;
+        propagation.light(beam.row, beam.col, beam.direction);
+
+        let step = step(manifold, beam);
+        if let Some((row, col)) = step.entered {
+            propagation.light(row, col, beam.direction);
+        }
+        if let Some((row, col)) = step.split {
+            propagation.splits.insert((row, col, beam.direction));
+        }
+        for next in step.moves {
+            match next {
+                Move::Beam(next) => pending.push(next),
+                Move::End(outcome) => {
+                    propagation.outcomes.insert(outcome);
+                }
+            }
+        }
+    }
+    propagation
+}
+
+/// The beams loop back on themselves, so there are infinitely many timelines
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CycleError {
+    /// A beam state on the loop
+    pub beam: Beam,
+}
+
+impl fmt::Display for CycleError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "a beam returns to ({}, {}) moving {}, so the number of timelines is infinite",
+            self.beam.row, self.beam.col, self.beam.direction
+        )
+    }
+}
+
+impl std::error::Error for CycleError {}
+
+/// Timeline counts for every way a timeline can end (Part 2)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Timelines<C> {
+    /// Timelines per counted outcome
+    pub outcomes: BTreeMap<Outcome, C>,
+    /// Timelines that left through a side under `SideExits::Dropped`; they
+    /// are not part of `total`
+    pub side_exits: BTreeMap<Outcome, C>,
+    /// Timelines split by each splitter, zero for splitters never reached
+    pub splitters: BTreeMap<(usize, usize), C>,
+    /// Timelines over all counted outcomes
+    pub total: C,
+}
+
+/// Why timelines couldn't be counted
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TimelineError {
+    Cycle(CycleError),
+    /// A count grew too large for the counter, first at this beam state
+    /// (or while adding up the exits, if `None`)
+    Overflow { beam: Option<Beam>, counter: String },
+}
+
+impl fmt::Display for TimelineError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TimelineError::Cycle(cycle) => cycle.fmt(f),
+            TimelineError::Overflow { beam: Some(beam), counter } => write!(
+                f,
+                "the timeline count at ({}, {}) moving {} is too large for {}",
+                beam.row, beam.col, beam.direction, counter
+            ),
+            TimelineError::Overflow { beam: None, counter } => {
+                write!(f, "the total number of timelines is too large for {}", counter)
+            }
+        }
+    }
+}
+
+impl std::error::Error for TimelineError {}
+
+impl From<CycleError> for TimelineError {
+    fn from(cycle: CycleError) -> Self {
+        TimelineError::Cycle(cycle)
+    }
+}
+
+/// Reachable beam states ordered so every state comes before the states it
+/// leads to. Fails if the states contain a loop.
+pub fn topological_order(manifold: &Manifold) -> Result<Vec<Beam>, CycleError> {
+    #[derive(Clone, Copy, PartialEq, Eq)]
+    enum Mark {
+        New,
+        OnStack,
+        Done,
+    }
+
+    let mut marks = vec![Mark::New; manifold.rows() * manifold.cols() * Direction::ALL.len()];
+    let mut post_order = Vec::new();
+
+    // Iterative depth-first search; each frame holds a beam and the
+    // successors not yet explored
+    for source in initial_beams(manifold) {
+        if marks[state_index(manifold, source)] != Mark::New {
+            continue;
+        }
+        marks[state_index(manifold, source)] = Mark::OnStack;
+        let mut stack = vec![(source, successors(manifold, source))];
+
+        while let Some((beam, next)) = stack.last_mut() {
+            let beam = *beam;
+            match next.pop() {
+                Some(child) => match marks[state_index(manifold, child)] {
+                    Mark::New => {
+                        marks[state_index(manifold, child)] = Mark::OnStack;
+                        stack.push((child, successors(manifold, child)));
+                    }
+                    Mark::OnStack => return Err(CycleError { beam: child }),
+                    Mark::Done => {}
+                },
+                None => {
+                    marks[state_index(manifold, beam)] = Mark::Done;
+                    post_order.push(beam);
+                    stack.pop();
+                }
+            }
+        }
+    }
+
+    post_order.reverse();
+    Ok(post_order)
+}
+
+fn successors(manifold: &Manifold, beam: Beam) -> Vec<Beam> {
+    step(manifold, beam)
+        .moves
+        .into_iter()
+        .filter_map(|m| match m {
+            Move::Beam(next) => Some(next),
+            Move::End(_) => None,
+        })
+        .collect()
+}
+
+/// Count the timelines ending at each outcome. Every split doubles the
+/// timelines passing through it; timelines are never merged. Timelines that
+/// leave through a side are set apart unless `side_exits` counts them.
+pub fn count_timelines<C: Counter>(
+    manifold: &Manifold,
+    counter: &C,
+    side_exits: SideExits,
+) -> Result<Timelines<C::Count>, TimelineError> {
+    let order = topological_order(manifold)?;
+    let mut counts = vec![counter.zero(); manifold.rows() * manifold.cols() * Direction::ALL.len()];
+    let mut outcomes = BTreeMap::new();
+    let mut dropped = BTreeMap::new();
+    let mut splitters: BTreeMap<(usize, usize), C::Count> =
+        manifold.splitters().into_iter().map(|cell| (cell, counter.zero())).collect();
+
+    let add = |total: &mut C::Count, count: &C::Count, beam: Option<Beam>| -> Result<(), TimelineError> {
+        *total = counter
+            .add(total, count)
+            .ok_or_else(|| TimelineError::Overflow { beam, counter: counter.name() })?;
+        Ok(())
+    };
+
+    for beam in initial_beams(manifold) {
+        add(&mut counts[state_index(manifold, beam)], &counter.one(), Some(beam))?;
+    }
+
+    // Every state in the order is reachable, so each one's exits are
+    // recorded even when its count is zero modulo M
+    for beam in order {
+        let count = counts[state_index(manifold, beam)].clone();
+        let step = step(manifold, beam);
+        if let Some(cell) = step.split {
+            add(splitters.get_mut(&cell).unwrap(), &count, Some(beam))?;
+        }
+        for next in step.moves {
+            match next {
+                Move::Beam(next) => add(&mut counts[state_index(manifold, next)], &count, Some(next))?,
+                Move::End(outcome) => {
+                    let ends = if side_exits.counts(outcome) { &mut outcomes } else { &mut dropped };
+                    add(ends.entry(outcome).or_insert_with(|| counter.zero()), &count, Some(beam))?
+                }
+            }
+        }
+    }
+
+    let mut total = counter.zero();
+    for count in outcomes.values() {
+        add(&mut total, count, None)?;
+    }
+    Ok(Timelines { outcomes, side_exits: dropped, splitters, total })
+}
@@ -1,6 +1,10 @@
-use std::collections::HashMap;
-use std::fs::File;
-use std::io::{self, BufRead, BufReader};
+use laboratories::{
+    Checked, Counter, Exact, Manifold, Modulo, Ranked, RuleTable, SideExits, TimelineSampler, Timelines, XorShift, count_timelines,
+    enumerate,
+};
+use std::env;
+use std::fs;
+use std::io;
 
 /// Advent of Code 2025 - Day 7: Laboratories (Part 2)
 ///
@@ -8,80 +12,207 @@ use std::io::{self, BufRead, BufReader};
 /// Each split creates a new timeline. Count the total number of timelines
 /// after the particle completes all possible journeys.
 ///
-/// Key insight: Track how many timelines are at each column position.
+/// Key insight: Track how many timelines are in each beam state.
 /// When a timeline hits a splitter, it becomes 2 timelines (left and right).
-
+/// Timelines that leave through the bottom or top or hit an absorber count;
+/// a splitter branch or beam that leaves through a side is dropped, as a
+/// branch falling off the grid always was. A loop through mirrors would make
+/// the count infinite and is reported. `--side-exits` counts those side
+/// exits too, which changes the total.
+///
+/// Usage: quantum [--rule C=RULE]... [--side-exits] [--exits [--csv] [--normalize]]
+///        [--exact | --mod M] [--enumerate N] [--sample N [--seed S]]
+///
+/// `--exits` breaks the total down by where the timelines end: the column
+/// for the top and bottom edges, the row for the sides. `--csv` prints that
+/// as CSV for plotting and `--normalize` adds each exit's share of the total.
+/// Without `--side-exits`, the table lists the dropped side exits in a
+/// section of their own, outside the total; the CSV leaves them out.
+///
+/// Counts are u128 and a count that overflows is reported, never wrapped.
+/// `--exact` counts with big integers instead; `--mod M` gives every count
+/// modulo M (and can't be normalized).
+///
+/// `--enumerate N` lists the first N timelines (left before right at every
+/// split) and `--sample N` draws N uniformly random ones, seeded with
+/// `--seed S` (default 2025). Each is printed as its source, the splitters
+/// it passes with the branch taken (L or R) and where it ends. Sampling
+/// counts in u128 like the total, or with big integers under `--exact`;
+/// it can't be combined with `--mod`, since residues can't pick a timeline.
 fn main() -> io::Result<()> {
-    let file = File::open("input.txt")?;
-    let reader = BufReader::new(file);
-
-    // Parse the grid
-    let grid: Vec<Vec<char>> = reader
-        .lines()
-        .filter_map(|l| l.ok())
-        .filter(|l| !l.is_empty())
-        .map(|l| l.chars().collect())
-        .collect();
-
-    let rows = grid.len();
-    let cols = if rows > 0 { grid[0].len() } else { 0 };
-
-    // Find the starting position 'S'
-    let mut start_col = 0;
-    let mut current_row = 0;
-    for row in 0..rows {
-        for col in 0..cols {
-            if grid[row][col] == 'S' {
-                start_col = col;
-                current_row = row;
+    let invalid = |e: String| io::Error::new(io::ErrorKind::InvalidInput, e);
+    let (rules, rest) = RuleTable::from_args(env::args().skip(1)).map_err(invalid)?;
+
+    let mut side_exits = SideExits::Dropped;
+    let mut exits = false;
+    let mut csv = false;
+    let mut normalize = false;
+    let mut exact = false;
+    let mut modulus: Option<u128> = None;
+    let mut enumerate_count = 0;
+    let mut sample_count = 0;
+    let mut seed = 2025;
+    let mut rest = rest.into_iter();
+    while let Some(arg) = rest.next() {
+        match arg.as_str() {
+            "--side-exits" => side_exits = SideExits::Counted,
+            "--exits" => exits = true,
+            "--csv" => csv = true,
+            "--normalize" => normalize = true,
+            "--exact" => exact = true,
+            "--mod" => {
+                let value = rest.next().ok_or_else(|| invalid("--mod needs a modulus".to_string()))?;
+                let m = value.parse().ok().filter(|&m| m > 0);
+                modulus = Some(m.ok_or_else(|| invalid(format!("'{}' is not a positive modulus", value)))?);
             }
+            "--enumerate" | "--sample" | "--seed" => {
+                let value = rest.next().ok_or_else(|| invalid(format!("{} needs a number", arg)))?;
+                let number = value.parse().map_err(|_| invalid(format!("'{}' is not a number", value)))?;
+                match arg.as_str() {
+                    "--enumerate" => enumerate_count = number as usize,
+                    "--sample" => sample_count = number as usize,
+                    _ => seed = number,
+                }
+            }
+            _ => return Err(invalid(format!("unknown argument '{}'", arg))),
         }
     }
+    if (csv || normalize) && !exits {
+        return Err(invalid("--csv and --normalize only apply to --exits".to_string()));
+    }
+    if exact && modulus.is_some() {
+        return Err(invalid("--exact and --mod can't be combined".to_string()));
+    }
+    if sample_count > 0 && modulus.is_some() {
+        return Err(invalid("--sample needs actual counts, not residues; use --exact instead of --mod".to_string()));
+    }
+    if normalize && modulus.is_some() {
+        return Err(invalid("--normalize needs actual counts, not residues".to_string()));
+    }
 
-    // Track timelines: column -> number of timelines at that column
-    // Start with 1 timeline at the starting column
-    let mut timelines: HashMap<usize, u128> = HashMap::new();
-    timelines.insert(start_col, 1);
+    let input = fs::read_to_string("input.txt")?;
+    let manifold = Manifold::parse(&input, rules);
 
-    // Simulate the particle moving downward
-    while current_row < rows - 1 {
-        current_row += 1;
+    let data_error = |e: String| io::Error::new(io::ErrorKind::InvalidData, e);
+    if enumerate_count > 0 {
+        let timelines = enumerate(&manifold, enumerate_count, side_exits).map_err(|e| data_error(e.to_string()))?;
+        for (i, timeline) in timelines.iter().enumerate() {
+            println!("{}: {}", i + 1, timeline);
+        }
+        println!();
+    }
+    if sample_count > 0 {
+        if exact {
+            print_samples(&manifold, Exact, side_exits, sample_count, seed)?;
+        } else {
+            print_samples(&manifold, Checked, side_exits, sample_count, seed)?;
+        }
+    }
 
-        let mut next_timelines: HashMap<usize, u128> = HashMap::new();
+    let report = Report { side_exits, exits, csv, normalize };
+    match modulus {
+        Some(m) => report.run(&manifold, &Modulo(m)),
+        None if exact => report.run(&manifold, &Exact),
+        None => report.run(&manifold, &Checked),
+    }
+}
 
-        for (&col, &count) in &timelines {
-            if col >= cols {
-                continue;
-            }
+/// Draw and print `count` uniformly random timelines
+fn print_samples<C: Ranked>(manifold: &Manifold, counter: C, side_exits: SideExits, count: usize, seed: u64) -> io::Result<()> {
+    let sampler =
+        TimelineSampler::new(manifold, counter, side_exits).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e.to_string()))?;
+    let mut rng = XorShift::new(seed);
+    for i in 0..count {
+        let Some(timeline) = sampler.sample(manifold, &mut rng) else {
+            break;
+        };
+        println!("sample {}: {}", i + 1, timeline);
+    }
+    println!();
+    Ok(())
+}
 
-            match grid[current_row][col] {
-                '^' => {
-                    // Each timeline splits into 2 timelines (left and right)
-                    if col > 0 {
-                        *next_timelines.entry(col - 1).or_insert(0) += count;
-                    }
-                    if col + 1 < cols {
-                        *next_timelines.entry(col + 1).or_insert(0) += count;
-                    }
-                }
-                _ => {
-                    // Timeline continues downward
-                    *next_timelines.entry(col).or_insert(0) += count;
-                }
-            }
+/// What to print besides the total
+struct Report {
+    side_exits: SideExits,
+    exits: bool,
+    csv: bool,
+    normalize: bool,
+}
+
+impl Report {
+    fn run<C: Counter>(&self, manifold: &Manifold, counter: &C) -> io::Result<()> {
+        // Sum all timelines across all outcomes
+        let timelines = count_timelines(manifold, counter, self.side_exits)
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e.to_string()))?;
+
+        if self.exits && self.csv {
+            print_csv(&timelines, counter, self.normalize);
+            return Ok(());
+        }
+        if self.exits {
+            print_table(&timelines, counter, self.normalize);
+            println!();
         }
 
-        timelines = next_timelines;
+        println!("Total timelines: {}", timelines.total);
+        Ok(())
+    }
+}
 
-        // If no timelines left, stop
-        if timelines.is_empty() {
-            break;
+/// Each exit's share of all timelines
+fn probability<C: Counter>(counter: &C, count: &C::Count, total: &C::Count) -> f64 {
+    match (counter.to_f64(count), counter.to_f64(total)) {
+        (Some(count), Some(total)) if total > 0.0 => count / total,
+        _ => 0.0,
+    }
+}
+
+/// One line per exit, in edge order, then any dropped side exits apart
+fn print_table<C: Counter>(timelines: &Timelines<C::Count>, counter: &C, normalize: bool) {
+    let total = &timelines.total;
+    let labels: Vec<String> = timelines.outcomes.keys().map(|o| o.to_string()).collect();
+    let dropped: Vec<String> = timelines.side_exits.keys().map(|o| o.to_string()).collect();
+    let label_width = labels.iter().chain(&dropped).map(String::len).max().unwrap_or(0).max("Exit".len());
+    let counts = timelines.side_exits.values().map(|c| c.to_string().len());
+    let count_width = counts.fold(total.to_string().len(), usize::max).max("Timelines".len());
+
+    if normalize {
+        println!("{:<lw$}  {:>cw$}  Probability", "Exit", "Timelines", lw = label_width, cw = count_width);
+    } else {
+        println!("{:<lw$}  {:>cw$}", "Exit", "Timelines", lw = label_width, cw = count_width);
+    }
+    for (label, count) in labels.iter().zip(timelines.outcomes.values()) {
+        if normalize {
+            let p = probability(counter, count, total);
+            println!("{:<lw$}  {:>cw$}  {:.9}", label, count, p, lw = label_width, cw = count_width);
+        } else {
+            println!("{:<lw$}  {:>cw$}", label, count, lw = label_width, cw = count_width);
         }
     }
 
-    // Sum all timelines across all positions
-    let total_timelines: u128 = timelines.values().sum();
+    if !dropped.is_empty() {
+        println!();
+        println!("Side exits (not in the total)");
+        for (label, count) in dropped.iter().zip(timelines.side_exits.values()) {
+            println!("{:<lw$}  {:>cw$}", label, count, lw = label_width, cw = count_width);
+        }
+    }
+}
 
-    println!("Total timelines: {}", total_timelines);
-    Ok(())
+/// `exit,row,col,timelines[,probability]`, with row or column left empty
+/// where the exit doesn't have one
+fn print_csv<C: Counter>(timelines: &Timelines<C::Count>, counter: &C, normalize: bool) {
+    let total = &timelines.total;
+    let field = |value: Option<usize>| value.map_or(String::new(), |v| v.to_string());
+
+    println!("exit,row,col,timelines{}", if normalize { ",probability" } else { "" });
+    for (&outcome, count) in &timelines.outcomes {
+        let (row, col) = outcome.position();
+        print!("{},{},{},{}", outcome.edge(), field(row), field(col), count);
+        if normalize {
+            print!(",{}", probability(counter, count, total));
+        }
+        println!();
+    }
 }
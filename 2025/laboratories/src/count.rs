@@ -0,0 +1,108 @@
+use bignum::BigUint;
+use std::fmt::Display;
+
+/// The number system timeline counts are kept in.
+///
+/// Counting only ever adds, so a counter needs a zero, a one and a sum that
+/// reports overflow. `Checked` is the default, `Exact` never overflows and
+/// `Modulo` keeps counts reduced modulo a fixed number.
+pub trait Counter {
+    type Count: Clone + PartialEq + Display;
+
+    /// How the counts are kept, for error messages
+    fn name(&self) -> String;
+    fn zero(&self) -> Self::Count;
+    fn one(&self) -> Self::Count;
+    /// `a + b`, or `None` if it can't be represented
+    fn add(&self, a: &Self::Count, b: &Self::Count) -> Option<Self::Count>;
+    /// The count as a float, for probabilities; `None` when that makes no
+    /// sense (residues)
+    fn to_f64(&self, count: &Self::Count) -> Option<f64>;
+}
+
+/// `u128` counts; overflow is an error rather than wrapping
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Checked;
+
+impl Counter for Checked {
+    type Count = u128;
+
+    fn name(&self) -> String {
+        "u128".to_string()
+    }
+
+    fn zero(&self) -> u128 {
+        0
+    }
+
+    fn one(&self) -> u128 {
+        1
+    }
+
+    fn add(&self, a: &u128, b: &u128) -> Option<u128> {
+        a.checked_add(*b)
+    }
+
+    fn to_f64(&self, count: &u128) -> Option<f64> {
+        Some(*count as f64)
+    }
+}
+
+/// Big-integer counts, exact for any grid
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Exact;
+
+impl Counter for Exact {
+    type Count = BigUint;
+
+    fn name(&self) -> String {
+        "big integers".to_string()
+    }
+
+    fn zero(&self) -> BigUint {
+        BigUint::zero()
+    }
+
+    fn one(&self) -> BigUint {
+        BigUint::one()
+    }
+
+    fn add(&self, a: &BigUint, b: &BigUint) -> Option<BigUint> {
+        Some(a + b)
+    }
+
+    fn to_f64(&self, count: &BigUint) -> Option<f64> {
+        Some(count.to_f64())
+    }
+}
+
+/// Counts modulo a fixed, non-zero modulus
+#[derive(Debug, Clone, Copy)]
+pub struct Modulo(pub u128);
+
+impl Counter for Modulo {
+    type Count = u128;
+
+    fn name(&self) -> String {
+        format!("integers modulo {}", self.0)
+    }
+
+    fn zero(&self) -> u128 {
+        0
+    }
+
+    fn one(&self) -> u128 {
+        1 % self.0
+    }
+
+    /// Both operands are already reduced, so subtracting from the modulus
+    /// avoids overflowing even when it is close to `u128::MAX`
+    fn add(&self, a: &u128, b: &u128) -> Option<u128> {
+        let room = self.0 - b;
+        Some(if *a >= room { a - room } else { a + b })
+    }
+
+    fn to_f64(&self, _: &u128) -> Option<f64> {
+        None
+    }
+}
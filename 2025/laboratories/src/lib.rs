@@ -0,0 +1,23 @@
+//! Advent of Code 2025 - Day 7: Laboratories
+//!
+//! A general tachyon beam engine shared by both parts. The grid is read once
+//! into a `Manifold`, each character is mapped to a `Rule` through a table,
+//! and beams are moved one cell at a time. Part 1 counts split events over
+//! the merged beams (`propagate`); Part 2 counts timelines, i.e. paths through
+//! the beam states (`count_timelines`), in whichever number system the
+//! `Counter` provides. Single timelines can be listed or sampled (`timeline`),
+//! and `analyze` finds the parts of a layout that could be removed.
+
+pub mod analysis;
+pub mod beam;
+pub mod count;
+pub mod manifold;
+pub mod render;
+pub mod timeline;
+
+pub use analysis::{Analysis, analyze};
+pub use beam::{Beam, CycleError, Direction, Move, Outcome, Propagation, SideExits, Step, TimelineError, Timelines, count_timelines, propagate, step};
+pub use count::{Checked, Counter, Exact, Modulo};
+pub use manifold::{Manifold, Rule, RuleTable};
+pub use render::print_overlay;
+pub use timeline::{Ranked, Timeline, TimelineSampler, XorShift, enumerate};
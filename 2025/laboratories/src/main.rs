@@ -1,98 +1,89 @@
-use std::collections::HashSet;
-use std::fs::File;
-use std::io::{self, BufRead, BufReader};
+use laboratories::{Exact, Manifold, RuleTable, SideExits, analyze, count_timelines, print_overlay, propagate};
+use std::env;
+use std::fs;
+use std::io;
 
 /// Advent of Code 2025 - Day 7: Laboratories
 ///
 /// Problem: Simulate tachyon beams traveling through a manifold.
-/// - Beams start at 'S' and travel downward
+/// - Beams start at every 'S' and travel downward
 /// - Empty space (.) allows beams to pass through
 /// - Splitters (^) stop the beam and emit two new beams (left and right)
+/// - Mirrors (/ and \) turn beams, absorbers (#) stop them
 /// - Count how many times a beam is split
-
+///
+/// Usage: laboratories [--rule C=RULE]... [--render [--color]] [--hits]
+///        [--analyze]
+///
+/// `--rule` makes character C behave as one of: empty, source, splitter,
+/// slash, backslash, absorber (e.g. `--rule *=splitter`).
+///
+/// `--render` redraws the grid with the beam paths on it and splitters that
+/// were never hit marked; `--color` shows the same with ANSI colors.
+/// `--hits` lists every splitter with the number of quantum timelines it
+/// splits, counted exactly.
+///
+/// `--analyze` lists splitters that never split a beam, columns no beam occupies and
+/// hit splitters whose removal changes neither the split count nor the
+/// timeline total.
 fn main() -> io::Result<()> {
-    let file = File::open("input.txt")?;
-    let reader = BufReader::new(file);
-
-    // Parse the grid
-    let grid: Vec<Vec<char>> = reader
-        .lines()
-        .filter_map(|l| l.ok())
-        .filter(|l| !l.is_empty())
-        .map(|l| l.chars().collect())
-        .collect();
-
-    let rows = grid.len();
-    let cols = if rows > 0 { grid[0].len() } else { 0 };
-
-    // Find the starting position 'S'
-    let mut start_col = 0;
-    for row in 0..rows {
-        for col in 0..cols {
-            if grid[row][col] == 'S' {
-                start_col = col;
-            }
+    let (rules, rest) =
+        RuleTable::from_args(env::args().skip(1)).map_err(|e| io::Error::new(io::ErrorKind::InvalidInput, e))?;
+    let mut render = false;
+    let mut color = false;
+    let mut hits = false;
+    let mut analysis = false;
+    for arg in rest {
+        match arg.as_str() {
+            "--render" => render = true,
+            "--color" => color = true,
+            "--hits" => hits = true,
+            "--analyze" => analysis = true,
+            _ => return Err(io::Error::new(io::ErrorKind::InvalidInput, format!("unknown argument '{}'", arg))),
         }
     }
-
-    // Track active beams as (row, col) - all beams travel downward
-    // Use a set to handle beams merging at the same position
-    let mut beams: HashSet<usize> = HashSet::new();
-    beams.insert(start_col);
-
-    let mut split_count = 0;
-    let mut current_row = 0;
-
-    // Find the row where S is located
-    for row in 0..rows {
-        if grid[row].iter().any(|&c| c == 'S') {
-            current_row = row;
-            break;
-        }
+    if color && !render {
+        return Err(io::Error::new(io::ErrorKind::InvalidInput, "--color only applies to --render"));
     }
 
-    // Simulate beams moving downward
-    while current_row < rows - 1 {
-        current_row += 1;
+    let input = fs::read_to_string("input.txt")?;
+    let manifold = Manifold::parse(&input, rules);
 
-        let mut next_beams: HashSet<usize> = HashSet::new();
+    // Beams meeting in the same cell merge, so each splitter splits a
+    // beam coming from a given direction at most once
+    let propagation = propagate(&manifold);
 
-        for &col in &beams {
-            if col >= cols {
-                continue;
-            }
-
-            match grid[current_row][col] {
-                '^' => {
-                    // Beam hits a splitter - split into left and right
-                    split_count += 1;
-                    if col > 0 {
-                        next_beams.insert(col - 1);
-                    }
-                    if col + 1 < cols {
-                        next_beams.insert(col + 1);
-                    }
-                }
-                '.' | 'S' => {
-                    // Beam continues downward
-                    next_beams.insert(col);
-                }
-                _ => {
-                    // Any other character - beam continues
-                    next_beams.insert(col);
-                }
-            }
+    if render {
+        print_overlay(&manifold, &propagation, color);
+        println!();
+    }
+    if hits {
+        let timelines =
+            count_timelines(&manifold, &Exact, SideExits::Dropped).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e.to_string()))?;
+        println!("{:>5}  {:>5}  Timelines split", "Row", "Col");
+        for (&(row, col), count) in &timelines.splitters {
+            println!("{:>5}  {:>5}  {}", row, col, count);
         }
+        println!();
+    }
 
-        beams = next_beams;
-
-        // If no beams left, stop
-        if beams.is_empty() {
-            break;
-        }
+    if analysis {
+        let analysis = analyze(&manifold).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e.to_string()))?;
+        let cells = |cells: &[(usize, usize)]| -> String {
+            cells.iter().map(|(row, col)| format!("({}, {})", row, col)).collect::<Vec<_>>().join(" ")
+        };
+        let columns: Vec<String> = analysis.dark_columns.iter().map(|col| col.to_string()).collect();
+
+        println!("Splitters that never split a beam ({}): {}", analysis.unreached.len(), cells(&analysis.unreached));
+        println!("Columns no beam occupies ({}): {}", analysis.dark_columns.len(), columns.join(" "));
+        println!(
+            "Hit splitters that can be removed without changing either answer ({}): {}",
+            analysis.redundant.len(),
+            cells(&analysis.redundant)
+        );
+        println!();
     }
 
-    println!("Total splits: {}", split_count);
+    println!("Total splits: {}", propagation.split_count());
     Ok(())
 }
-
@@ -0,0 +1,167 @@
+use std::collections::HashMap;
+use std::fmt;
+use std::str::FromStr;
+
+/// What a cell does to a beam entering it
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Rule {
+    /// The beam carries on unchanged
+    Empty,
+    /// Emits a downward beam; other beams pass through
+    Source,
+    /// A vertical beam stops and two beams leave from the cells on either
+    /// side, still moving the same way; a horizontal beam passes through
+    Splitter,
+    /// `/`: down turns left, up turns right, and vice versa
+    Slash,
+    /// `\`: down turns right, up turns left, and vice versa
+    Backslash,
+    /// The beam stops here
+    Absorber,
+}
+
+impl Rule {
+    pub const ALL: [Rule; 6] = [Rule::Empty, Rule::Source, Rule::Splitter, Rule::Slash, Rule::Backslash, Rule::Absorber];
+
+    /// The command line name of this rule
+    pub fn name(self) -> &'static str {
+        match self {
+            Rule::Empty => "empty",
+            Rule::Source => "source",
+            Rule::Splitter => "splitter",
+            Rule::Slash => "slash",
+            Rule::Backslash => "backslash",
+            Rule::Absorber => "absorber",
+        }
+    }
+}
+
+impl FromStr for Rule {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Rule::ALL.into_iter().find(|r| r.name() == s).ok_or_else(|| {
+            let names: Vec<&str> = Rule::ALL.iter().map(|r| r.name()).collect();
+            format!("unknown rule '{}' (expected one of: {})", s, names.join(", "))
+        })
+    }
+}
+
+impl fmt::Display for Rule {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+/// Which rule each character of the grid follows.
+/// Characters without an entry are empty space.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RuleTable {
+    rules: HashMap<char, Rule>,
+}
+
+impl Default for RuleTable {
+    /// `S` source, `^` splitter, `/` and `\` mirrors, `#` absorber
+    fn default() -> Self {
+        let rules = [('S', Rule::Source), ('^', Rule::Splitter), ('/', Rule::Slash), ('\\', Rule::Backslash), ('#', Rule::Absorber)];
+        RuleTable { rules: rules.into_iter().collect() }
+    }
+}
+
+impl RuleTable {
+    pub fn rule(&self, cell: char) -> Rule {
+        self.rules.get(&cell).copied().unwrap_or(Rule::Empty)
+    }
+
+    pub fn set(&mut self, cell: char, rule: Rule) {
+        self.rules.insert(cell, rule);
+    }
+
+    /// Start from the default table and apply every `--rule C=NAME` flag.
+    /// Arguments that aren't ours are returned for the caller to handle.
+    pub fn from_args(args: impl IntoIterator<Item = String>) -> Result<(Self, Vec<String>), String> {
+        let mut table = RuleTable::default();
+        let mut rest = Vec::new();
+
+        let mut args = args.into_iter();
+        while let Some(arg) = args.next() {
+            if arg != "--rule" {
+                rest.push(arg);
+                continue;
+            }
+
+            let value = args.next().ok_or("--rule needs a value like '*=splitter'")?;
+            let mut chars = value.chars();
+            match (chars.next(), chars.next()) {
+                (Some(cell), Some('=')) => table.set(cell, chars.as_str().parse()?),
+                _ => return Err(format!("'{}' is not of the form C=RULE", value)),
+            }
+        }
+        Ok((table, rest))
+    }
+}
+
+/// A Day 7 manifold: the grid of cells and the rules they follow
+#[derive(Debug, Clone)]
+pub struct Manifold {
+    grid: Vec<Vec<char>>,
+    cols: usize,
+    pub rules: RuleTable,
+}
+
+impl Manifold {
+    /// Parse the grid, skipping empty lines. The widest line sets the
+    /// width; shorter lines are treated as padded with empty cells.
+    pub fn parse(input: &str, rules: RuleTable) -> Self {
+        let grid: Vec<Vec<char>> = input
+            .lines()
+            .filter(|l| !l.is_empty())
+            .map(|l| l.chars().collect())
+            .collect();
+        let cols = grid.iter().map(Vec::len).max().unwrap_or(0);
+        Manifold { grid, cols, rules }
+    }
+
+    pub fn rows(&self) -> usize {
+        self.grid.len()
+    }
+
+    pub fn cols(&self) -> usize {
+        self.cols
+    }
+
+    /// The character at (row, col); '.' past the end of a short line
+    pub fn cell(&self, row: usize, col: usize) -> char {
+        self.grid[row].get(col).copied().unwrap_or('.')
+    }
+
+    /// Replace the character at (row, col), padding a short line if needed
+    pub fn set_cell(&mut self, row: usize, col: usize, cell: char) {
+        let line = &mut self.grid[row];
+        if line.len() <= col {
+            line.resize(col + 1, '.');
+        }
+        line[col] = cell;
+    }
+
+    pub fn rule_at(&self, row: usize, col: usize) -> Rule {
+        self.rules.rule(self.cell(row, col))
+    }
+
+    /// Every source cell, top to bottom and left to right
+    pub fn sources(&self) -> Vec<(usize, usize)> {
+        self.cells_with(Rule::Source)
+    }
+
+    /// Every splitter cell, top to bottom and left to right
+    pub fn splitters(&self) -> Vec<(usize, usize)> {
+        self.cells_with(Rule::Splitter)
+    }
+
+    fn cells_with(&self, rule: Rule) -> Vec<(usize, usize)> {
+        (0..self.rows())
+            .flat_map(|row| (0..self.cols).map(move |col| (row, col)))
+            .filter(|&(row, col)| self.rule_at(row, col) == rule)
+            .collect()
+    }
+}
@@ -0,0 +1,84 @@
+//! Text overlay of the beams on the original grid.
+//!
+//! Empty cells a beam passed through are drawn as `|`, `-` or `+` (both
+//! ways). Splitters that split a beam keep their character, splitters no beam
+//! ever split are drawn as `o`. With color on, every character is kept and
+//! the same information is shown through ANSI colors instead.
+
+use crate::{Manifold, Propagation, Rule};
+use std::collections::BTreeSet;
+
+const RESET: &str = "\x1b[0m";
+/// Beam: yellow
+const LIT: &str = "\x1b[33m";
+/// Splitter that split a beam: bold green
+const HIT: &str = "\x1b[1;32m";
+/// Splitter that never split a beam: dim red
+const UNUSED: &str = "\x1b[2;31m";
+
+/// How a cell is drawn in the overlay
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Mark {
+    Plain,
+    Lit,
+    Hit,
+    Unused,
+}
+
+/// Print the grid with the beam paths drawn over it, then a legend
+pub fn print_overlay(manifold: &Manifold, propagation: &Propagation, color: bool) {
+    let hit = propagation.splitters_hit();
+
+    for row in 0..manifold.rows() {
+        let mut line = String::new();
+        for col in 0..manifold.cols() {
+            let (symbol, mark) = cell(manifold, propagation, &hit, row, col, color);
+            match (color, mark) {
+                (true, Mark::Lit) => line.push_str(LIT),
+                (true, Mark::Hit) => line.push_str(HIT),
+                (true, Mark::Unused) => line.push_str(UNUSED),
+                _ => {}
+            }
+            line.push(symbol);
+            if color && mark != Mark::Plain {
+                line.push_str(RESET);
+            }
+        }
+        println!("{}", line);
+    }
+
+    println!();
+    if color {
+        println!("{}beam{}, {}splitter hit{}, {}splitter never hit{}", LIT, RESET, HIT, RESET, UNUSED, RESET);
+    } else {
+        println!("| - + beam, o splitter never hit");
+    }
+}
+
+fn cell(
+    manifold: &Manifold,
+    propagation: &Propagation,
+    hit: &BTreeSet<(usize, usize)>,
+    row: usize,
+    col: usize,
+    color: bool,
+) -> (char, Mark) {
+    let original = manifold.cell(row, col);
+    let lit = propagation.is_lit(row, col);
+
+    match manifold.rule_at(row, col) {
+        Rule::Splitter if hit.contains(&(row, col)) => (original, Mark::Hit),
+        Rule::Splitter if color => (original, Mark::Unused),
+        Rule::Splitter => ('o', Mark::Unused),
+        Rule::Empty if lit && !color => {
+            let symbol = match propagation.beam_axes(row, col) {
+                (true, true) => '+',
+                (true, false) => '|',
+                _ => '-',
+            };
+            (symbol, Mark::Lit)
+        }
+        _ if lit => (original, Mark::Lit),
+        _ => (original, Mark::Plain),
+    }
+}
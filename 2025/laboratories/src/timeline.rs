@@ -0,0 +1,285 @@
+//! Individual timelines through the quantum manifold.
+//!
+//! A timeline is fixed by its source and the branch taken at every split,
+//! left before right. `enumerate` lists timelines in that lexicographic
+//! order; `TimelineSampler` picks them uniformly at random by unranking a
+//! random index with the number of timelines below every beam state, kept
+//! in any `Ranked` counter.
+
+use crate::beam::{initial_beams, state_index, topological_order};
+use crate::{Beam, Checked, Counter, CycleError, Direction, Exact, Manifold, Move, Outcome, SideExits, TimelineError, step};
+use bignum::BigUint;
+use std::fmt;
+
+/// One complete timeline
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Timeline {
+    /// The source cell it starts from
+    pub source: (usize, usize),
+    /// Every splitter it passes, with the branch taken there
+    pub splits: Vec<(usize, usize, Direction)>,
+    pub outcome: Outcome,
+}
+
+impl fmt::Display for Timeline {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "S({}, {})", self.source.0, self.source.1)?;
+        for &(row, col, branch) in &self.splits {
+            write!(f, " ^({}, {}){}", row, col, if branch == Direction::Left { 'L' } else { 'R' })?;
+        }
+        write!(f, " -> {}", self.outcome)
+    }
+}
+
+/// Splitters passed so far, with the branch taken at each
+type Splits = Vec<(usize, usize, Direction)>;
+
+/// The branch a split's `index`th move takes
+fn branch(index: usize) -> Direction {
+    if index == 0 { Direction::Left } else { Direction::Right }
+}
+
+/// The first `limit` timelines in lexicographic order: sources top to
+/// bottom and left to right, then left before right at every split. Only
+/// timelines that `side_exits` counts are listed.
+///
+/// Fails on a manifold whose beams loop, since the listing would never end.
+pub fn enumerate(manifold: &Manifold, limit: usize, side_exits: SideExits) -> Result<Vec<Timeline>, CycleError> {
+    topological_order(manifold)?;
+    let mut timelines = Vec::new();
+
+    // Depth-first, pushing right branches first so left ones pop first
+    let mut pending: Vec<((usize, usize), Move, Splits)> = initial_beams(manifold)
+        .into_iter()
+        .rev()
+        .map(|beam| ((beam.row, beam.col), Move::Beam(beam), Vec::new()))
+        .collect();
+
+    while timelines.len() < limit {
+        let Some((source, next, splits)) = pending.pop() else {
+            break;
+        };
+        let mut beam = match next {
+            Move::Beam(beam) => beam,
+            Move::End(outcome) => {
+                if side_exits.counts(outcome) {
+                    timelines.push(Timeline { source, splits, outcome });
+                }
+                continue;
+            }
+        };
+
+        // Follow the beam until it splits or ends
+        loop {
+            let step = step(manifold, beam);
+            if let Some((row, col)) = step.split {
+                for (i, next) in step.moves.into_iter().enumerate().rev() {
+                    let mut splits = splits.clone();
+                    splits.push((row, col, branch(i)));
+                    pending.push((source, next, splits));
+                }
+                break;
+            }
+            match step.moves[0] {
+                Move::Beam(next) => beam = next,
+                Move::End(outcome) => {
+                    if side_exits.counts(outcome) {
+                        timelines.push(Timeline { source, splits, outcome });
+                    }
+                    break;
+                }
+            }
+        }
+    }
+    Ok(timelines)
+}
+
+/// A counter whose counts can be compared, subtracted and drawn at random,
+/// which unranking needs. Residues can't be ordered, so `Modulo` has none.
+pub trait Ranked: Counter {
+    fn less(&self, a: &Self::Count, b: &Self::Count) -> bool;
+    /// `a - b`; `b` is never larger than `a`
+    fn sub(&self, a: &Self::Count, b: &Self::Count) -> Self::Count;
+    /// Uniform in `0..n`, without modulo bias. `n` must not be zero.
+    fn below(&self, rng: &mut XorShift, n: &Self::Count) -> Self::Count;
+}
+
+impl Ranked for Checked {
+    fn less(&self, a: &u128, b: &u128) -> bool {
+        a < b
+    }
+
+    fn sub(&self, a: &u128, b: &u128) -> u128 {
+        a - b
+    }
+
+    fn below(&self, rng: &mut XorShift, n: &u128) -> u128 {
+        rng.below(*n)
+    }
+}
+
+impl Ranked for Exact {
+    fn less(&self, a: &BigUint, b: &BigUint) -> bool {
+        a < b
+    }
+
+    fn sub(&self, a: &BigUint, b: &BigUint) -> BigUint {
+        a.checked_sub(b).expect("subtracting a larger count")
+    }
+
+    /// Draw as many random bits as `n` has and retry when the result is too
+    /// large; that happens less than half the time
+    fn below(&self, rng: &mut XorShift, n: &BigUint) -> BigUint {
+        let bits = n.bits() as u32;
+        let shift = BigUint::from(1u128 << 64);
+        let mask = BigUint::from(2u32).pow(bits);
+        loop {
+            let mut x = BigUint::zero();
+            for _ in 0..bits.div_ceil(64) {
+                x = &(&x * &shift) + &BigUint::from(rng.next_u64());
+            }
+            let (_, x) = x.div_rem(&mask).expect("mask is a power of two");
+            if x < *n {
+                return x;
+            }
+        }
+    }
+}
+
+/// Draws timelines uniformly at random.
+///
+/// `ways[state]` is the number of timelines from a beam state to the end,
+/// counted with the sampler's `Counter`: with `Checked` a manifold with more
+/// timelines than a `u128` holds is reported as an overflow, while `Exact`
+/// samples any manifold. Endings that `side_exits` drops count as no
+/// timeline, so they are never drawn.
+#[derive(Debug, Clone)]
+pub struct TimelineSampler<C: Ranked> {
+    counter: C,
+    side_exits: SideExits,
+    ways: Vec<C::Count>,
+    sources: Vec<Beam>,
+    total: C::Count,
+}
+
+impl<C: Ranked> TimelineSampler<C> {
+    pub fn new(manifold: &Manifold, counter: C, side_exits: SideExits) -> Result<Self, TimelineError> {
+        let order = topological_order(manifold)?;
+        let mut ways = vec![counter.zero(); manifold.rows() * manifold.cols() * Direction::ALL.len()];
+        let overflow = |beam| TimelineError::Overflow { beam: Some(beam), counter: counter.name() };
+        let (zero, one) = (counter.zero(), counter.one());
+
+        // Later states first, so every successor is already counted
+        for &beam in order.iter().rev() {
+            let mut count = counter.zero();
+            for next in step(manifold, beam).moves {
+                let ways_after = match next {
+                    Move::Beam(next) => &ways[state_index(manifold, next)],
+                    Move::End(outcome) if side_exits.counts(outcome) => &one,
+                    Move::End(_) => &zero,
+                };
+                count = counter.add(&count, ways_after).ok_or_else(|| overflow(beam))?;
+            }
+            ways[state_index(manifold, beam)] = count;
+        }
+
+        let sources = initial_beams(manifold);
+        let mut total = counter.zero();
+        for &source in &sources {
+            total = counter
+                .add(&total, &ways[state_index(manifold, source)])
+                .ok_or(TimelineError::Overflow { beam: None, counter: counter.name() })?;
+        }
+        Ok(TimelineSampler { counter, side_exits, ways, sources, total })
+    }
+
+    /// Total number of timelines
+    pub fn total(&self) -> &C::Count {
+        &self.total
+    }
+
+    /// The timeline at `index` in lexicographic order, if there is one
+    pub fn timeline(&self, manifold: &Manifold, mut index: C::Count) -> Option<Timeline> {
+        let counter = &self.counter;
+        let (zero, one) = (counter.zero(), counter.one());
+
+        // Skip whole sources, then whole branches, until index falls inside one
+        let mut beam = None;
+        for &source in &self.sources {
+            let ways = &self.ways[state_index(manifold, source)];
+            if counter.less(&index, ways) {
+                beam = Some(source);
+                break;
+            }
+            index = counter.sub(&index, ways);
+        }
+        let mut beam = beam?;
+        let source = (beam.row, beam.col);
+        let mut splits = Vec::new();
+
+        loop {
+            let step = step(manifold, beam);
+            let mut chosen = None;
+            for (i, next) in step.moves.into_iter().enumerate() {
+                let ways = match next {
+                    Move::Beam(next) => &self.ways[state_index(manifold, next)],
+                    Move::End(outcome) if self.side_exits.counts(outcome) => &one,
+                    Move::End(_) => &zero,
+                };
+                if counter.less(&index, ways) {
+                    chosen = Some((i, next));
+                    break;
+                }
+                index = counter.sub(&index, ways);
+            }
+
+            let (i, next) = chosen?;
+            if let Some((row, col)) = step.split {
+                splits.push((row, col, branch(i)));
+            }
+            match next {
+                Move::Beam(next) => beam = next,
+                Move::End(outcome) => return Some(Timeline { source, splits, outcome }),
+            }
+        }
+    }
+
+    /// A timeline chosen uniformly at random
+    pub fn sample(&self, manifold: &Manifold, rng: &mut XorShift) -> Option<Timeline> {
+        if self.total == self.counter.zero() {
+            return None;
+        }
+        self.timeline(manifold, self.counter.below(rng, &self.total))
+    }
+}
+
+/// xorshift64* pseudo-random generator; the same seed gives the same samples
+#[derive(Debug, Clone)]
+pub struct XorShift {
+    state: u64,
+}
+
+impl XorShift {
+    pub fn new(seed: u64) -> Self {
+        // An all-zero state would only ever produce zeros
+        XorShift { state: if seed == 0 { 0x9E37_79B9_7F4A_7C15 } else { seed } }
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.state ^= self.state >> 12;
+        self.state ^= self.state << 25;
+        self.state ^= self.state >> 27;
+        self.state.wrapping_mul(0x2545_F491_4F6C_DD1D)
+    }
+
+    /// Uniform in `0..n`, without modulo bias. `n` must not be zero.
+    pub fn below(&mut self, n: u128) -> u128 {
+        let zone = u128::MAX - u128::MAX % n;
+        loop {
+            let x = (self.next_u64() as u128) << 64 | self.next_u64() as u128;
+            if x < zone {
+                return x % n;
+            }
+        }
+    }
+}
@@ -1,70 +1,66 @@
-use std::fs;
+use playground::{DisjointSet, NearestPairs, parse_positions, spanning_tree, write_components, write_dot, write_edges};
+use std::env;
+use std::fs::{self, File};
+use std::io::{self, BufWriter, Write};
+use std::process;
 
 /// Advent of Code 2025 - Day 8: Playground (Part 2)
 ///
 /// Keep connecting the closest unconnected pairs of junction boxes
 /// until they're all in one circuit. Find the last pair that completes
 /// the circuit and multiply their X coordinates.
-
+///
+/// Usage: circuit [--dot FILE] [--edges FILE] [--components FILE] [--first N]
+///
+/// The exports describe the spanning tree that connects every box: `--dot`
+/// as a Graphviz graph, `--edges` as a CSV edge list with distances and
+/// `--components` as a CSV of the circuit each box ends up in. With
+/// `--first N` they describe the first N connections instead, including
+/// pairs that were already in the same circuit.
 fn main() {
-    let input = fs::read_to_string("input.txt").unwrap();
-    
-    // Parse positions
-    let pos: Vec<(i64, i64, i64)> = input
-        .lines()
-        .filter(|l| !l.is_empty())
-        .map(|l| {
-            let c: Vec<i64> = l.split(',').map(|s| s.parse().unwrap()).collect();
-            (c[0], c[1], c[2])
-        })
-        .collect();
-    
-    let n = pos.len();
-    
-    // Generate all pairs sorted by distance
-    let mut pairs: Vec<(i64, usize, usize)> = Vec::new();
-    for i in 0..n {
-        for j in i+1..n {
-            let (dx, dy, dz) = (pos[i].0 - pos[j].0, pos[i].1 - pos[j].1, pos[i].2 - pos[j].2);
-            pairs.push((dx*dx + dy*dy + dz*dz, i, j));
-        }
-    }
-    pairs.sort_by_key(|p| p.0);
-    
-    // Union-Find with path compression and size tracking
-    let mut parent: Vec<usize> = (0..n).collect();
-    let mut size: Vec<usize> = vec![1; n];
-    
-    fn find(parent: &mut [usize], x: usize) -> usize {
-        if parent[x] != x { parent[x] = find(parent, parent[x]); }
-        parent[x]
-    }
-    
-    // Connect closest pairs until all are in one circuit
-    let mut last_pair: (usize, usize) = (0, 0);
-    let mut num_components = n;
-    
-    for &(_, i, j) in &pairs {
-        let (ri, rj) = (find(&mut parent, i), find(&mut parent, j));
-        if ri != rj {
-            // Union by size
-            if size[ri] < size[rj] {
-                parent[ri] = rj;
-                size[rj] += size[ri];
-            } else {
-                parent[rj] = ri;
-                size[ri] += size[rj];
+    let mut dot: Option<String> = None;
+    let mut edges_file: Option<String> = None;
+    let mut components_file: Option<String> = None;
+    let mut first: Option<usize> = None;
+    let mut args = env::args().skip(1);
+    while let Some(arg) = args.next() {
+        let mut value = || {
+            args.next().unwrap_or_else(|| {
+                eprintln!("{} needs a value", arg);
+                process::exit(2);
+            })
+        };
+        match arg.as_str() {
+            "--dot" => dot = Some(value()),
+            "--edges" => edges_file = Some(value()),
+            "--components" => components_file = Some(value()),
+            "--first" => {
+                first = Some(value().parse().unwrap_or_else(|_| {
+                    eprintln!("--first needs a non-negative number");
+                    process::exit(2);
+                }))
             }
-            num_components -= 1;
-            last_pair = (i, j);
-            
-            // Check if all connected
-            if num_components == 1 {
-                break;
+            _ => {
+                eprintln!("unknown argument '{}'", arg);
+                process::exit(2);
             }
         }
     }
+
+    let input = fs::read_to_string("input.txt").unwrap();
+    
+    // Parse positions
+    let pos = parse_positions(&input).unwrap_or_else(|e| {
+        eprintln!("Error: {}", e);
+        process::exit(1);
+    });
     
+    // Connecting the closest pairs until all are in one circuit keeps exactly
+    // the spanning tree of the closest pairs, ties broken by (i, j); the last
+    // pair connected is its longest edge
+    let tree = spanning_tree(&pos);
+    let last_pair = tree.last().map_or((0, 0), |&(_, i, j)| (i, j));
+
     // Get X coordinates of the last pair
     let x1 = pos[last_pair.0].0;
     let x2 = pos[last_pair.1].0;
@@ -73,4 +69,41 @@ fn main() {
              pos[last_pair.0].0, pos[last_pair.0].1, pos[last_pair.0].2,
              pos[last_pair.1].0, pos[last_pair.1].1, pos[last_pair.1].2);
     println!("Product of X coordinates: {}", x1 * x2);
+
+    if dot.is_none() && edges_file.is_none() && components_file.is_none() {
+        return;
+    }
+
+    // The first N connections, or the whole spanning tree
+    let edges = match first {
+        Some(n) => NearestPairs::new(&pos).take(n).collect(),
+        None => tree,
+    };
+    let mut circuits = DisjointSet::new(pos.len());
+    for &(_, i, j) in &edges {
+        circuits.union(i, j);
+    }
+    let members = circuits.components();
+
+    if let Some(path) = &dot {
+        export(path, |out| write_dot(out, &pos, &edges, &members));
+    }
+    if let Some(path) = &edges_file {
+        export(path, |out| write_edges(out, &pos, &edges));
+    }
+    if let Some(path) = &components_file {
+        export(path, |out| write_components(out, &pos, &members));
+    }
+}
+
+/// Write one export file, or exit with an error
+fn export(path: &str, write: impl FnOnce(&mut BufWriter<File>) -> io::Result<()>) {
+    let result = File::create(path).map(BufWriter::new).and_then(|mut out| {
+        write(&mut out)?;
+        out.flush()
+    });
+    if let Err(e) = result {
+        eprintln!("Error writing {}: {}", path, e);
+        process::exit(1);
+    }
 }
@@ -0,0 +1,97 @@
+/// Union-Find over the elements `0..n`.
+///
+/// `find` compresses paths iteratively, so even a long chain can't overflow
+/// the stack, and `union` hangs the smaller tree under the larger one, which
+/// keeps trees shallow to begin with.
+#[derive(Debug, Clone)]
+pub struct DisjointSet {
+    parent: Vec<usize>,
+    /// Component size, only meaningful at a root
+    size: Vec<usize>,
+    components: usize,
+}
+
+impl DisjointSet {
+    /// `n` elements, each in its own component
+    pub fn new(n: usize) -> Self {
+        DisjointSet { parent: (0..n).collect(), size: vec![1; n], components: n }
+    }
+
+    /// Number of elements
+    pub fn len(&self) -> usize {
+        self.parent.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.parent.is_empty()
+    }
+
+    /// The representative of `x`'s component
+    pub fn find(&mut self, x: usize) -> usize {
+        let mut root = x;
+        while self.parent[root] != root {
+            root = self.parent[root];
+        }
+
+        // Point everything on the path straight at the root
+        let mut current = x;
+        while self.parent[current] != root {
+            let next = self.parent[current];
+            self.parent[current] = root;
+            current = next;
+        }
+        root
+    }
+
+    /// Merge the components of `a` and `b`.
+    /// Returns false if they were already the same component.
+    pub fn union(&mut self, a: usize, b: usize) -> bool {
+        let (mut ra, mut rb) = (self.find(a), self.find(b));
+        if ra == rb {
+            return false;
+        }
+        if self.size[ra] < self.size[rb] {
+            std::mem::swap(&mut ra, &mut rb);
+        }
+        self.parent[rb] = ra;
+        self.size[ra] += self.size[rb];
+        self.components -= 1;
+        true
+    }
+
+    pub fn connected(&mut self, a: usize, b: usize) -> bool {
+        self.find(a) == self.find(b)
+    }
+
+    /// Number of elements in `x`'s component
+    pub fn size(&mut self, x: usize) -> usize {
+        let root = self.find(x);
+        self.size[root]
+    }
+
+    /// Number of components
+    pub fn component_count(&self) -> usize {
+        self.components
+    }
+
+    /// Sizes of all components, in no particular order
+    pub fn sizes(&self) -> Vec<usize> {
+        (0..self.len()).filter(|&x| self.parent[x] == x).map(|root| self.size[root]).collect()
+    }
+
+    /// Every component's elements in ascending order, components ordered by
+    /// their smallest element
+    pub fn components(&mut self) -> Vec<Vec<usize>> {
+        let mut index_of_root = vec![usize::MAX; self.len()];
+        let mut components: Vec<Vec<usize>> = Vec::with_capacity(self.components);
+        for x in 0..self.len() {
+            let root = self.find(x);
+            if index_of_root[root] == usize::MAX {
+                index_of_root[root] = components.len();
+                components.push(Vec::new());
+            }
+            components[index_of_root[root]].push(x);
+        }
+        components
+    }
+}
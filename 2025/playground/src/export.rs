@@ -0,0 +1,67 @@
+//! Export of a set of connections for visualisation.
+//!
+//! Edges are `(distance², i, j)` as produced by `NearestPairs`; boxes are
+//! referred to by their line order in the input, starting at 0. Circuits are
+//! numbered from 0 in order of their lowest-numbered box.
+
+use crate::Position;
+use std::io::{self, Write};
+
+/// Graphviz DOT: one cluster per circuit of more than one box, each box a
+/// node labelled with its coordinates and each edge labelled with its length
+pub fn write_dot(out: &mut impl Write, pos: &[Position], edges: &[(i64, usize, usize)], circuits: &[Vec<usize>]) -> io::Result<()> {
+    writeln!(out, "graph circuits {{")?;
+    writeln!(out, "    node [shape=box, fontsize=8];")?;
+
+    for (k, circuit) in circuits.iter().enumerate() {
+        let indent = if circuit.len() > 1 {
+            writeln!(out, "    subgraph cluster_{} {{", k)?;
+            writeln!(out, "        label=\"circuit {} ({} boxes)\";", k, circuit.len())?;
+            "        "
+        } else {
+            "    "
+        };
+        for &i in circuit {
+            let (x, y, z) = pos[i];
+            writeln!(out, "{}{} [label=\"{}\\n{},{},{}\"];", indent, i, i, x, y, z)?;
+        }
+        if circuit.len() > 1 {
+            writeln!(out, "    }}")?;
+        }
+    }
+
+    for &(d2, i, j) in edges {
+        writeln!(out, "    {} -- {} [label=\"{:.1}\"];", i, j, (d2 as f64).sqrt())?;
+    }
+    writeln!(out, "}}")
+}
+
+/// CSV edge list, in the order the connections were made
+pub fn write_edges(out: &mut impl Write, pos: &[Position], edges: &[(i64, usize, usize)]) -> io::Result<()> {
+    writeln!(out, "from,to,from_x,from_y,from_z,to_x,to_y,to_z,distance_squared,distance")?;
+    for &(d2, i, j) in edges {
+        let (a, b) = (pos[i], pos[j]);
+        writeln!(
+            out,
+            "{},{},{},{},{},{},{},{},{},{}",
+            i, j, a.0, a.1, a.2, b.0, b.1, b.2, d2, (d2 as f64).sqrt()
+        )?;
+    }
+    Ok(())
+}
+
+/// CSV with the circuit of every box, in box order
+pub fn write_components(out: &mut impl Write, pos: &[Position], circuits: &[Vec<usize>]) -> io::Result<()> {
+    let mut membership = vec![(0, 0); pos.len()];
+    for (k, circuit) in circuits.iter().enumerate() {
+        for &i in circuit {
+            membership[i] = (k, circuit.len());
+        }
+    }
+
+    writeln!(out, "box,x,y,z,circuit,circuit_size")?;
+    for (i, (&(x, y, z), (circuit, size))) in pos.iter().zip(membership).enumerate() {
+        writeln!(out, "{},{},{},{},{},{}", i, x, y, z, circuit, size)?;
+    }
+    Ok(())
+}
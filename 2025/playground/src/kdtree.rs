@@ -0,0 +1,89 @@
+use crate::Position;
+
+/// Boxes in a k-d tree: each node splits its boxes at the median of its
+/// widest axis, down to leaves of at most `LEAF` boxes
+pub(crate) struct KdTree {
+    /// Boxes with their indices in tree order, each node owning a contiguous run
+    pub(crate) boxes: Vec<(Position, usize)>,
+    /// Where each box ended up in `boxes`
+    pub(crate) slot: Vec<usize>,
+    pub(crate) nodes: Vec<Node>,
+}
+
+const LEAF: usize = 8;
+
+pub(crate) struct Node {
+    /// Bounding box of the node's boxes
+    pub(crate) min: Position,
+    pub(crate) max: Position,
+    /// The node's boxes are `boxes[start..end]`
+    pub(crate) start: usize,
+    pub(crate) end: usize,
+    /// Child nodes, or `None` for a leaf
+    pub(crate) children: Option<(usize, usize)>,
+}
+
+impl KdTree {
+    pub(crate) fn new(positions: &[Position]) -> Self {
+        let mut tree = KdTree {
+            boxes: positions.iter().copied().zip(0..).collect(),
+            slot: vec![0; positions.len()],
+            nodes: Vec::new(),
+        };
+        if !positions.is_empty() {
+            tree.build(0, positions.len());
+        }
+        for (k, &(_, i)) in tree.boxes.iter().enumerate() {
+            tree.slot[i] = k;
+        }
+        tree
+    }
+
+    /// Build the node for `boxes[start..end]` and everything below it,
+    /// returning its index
+    fn build(&mut self, start: usize, end: usize) -> usize {
+        let boxes = &mut self.boxes[start..end];
+        let mut min = (i64::MAX, i64::MAX, i64::MAX);
+        let mut max = (i64::MIN, i64::MIN, i64::MIN);
+        for &(p, _) in boxes.iter() {
+            min = (min.0.min(p.0), min.1.min(p.1), min.2.min(p.2));
+            max = (max.0.max(p.0), max.1.max(p.1), max.2.max(p.2));
+        }
+
+        let node = self.nodes.len();
+        self.nodes.push(Node { min, max, start, end, children: None });
+
+        if end - start > LEAF {
+            let (dx, dy, dz) = (max.0 - min.0, max.1 - min.1, max.2 - min.2);
+            let mid = (end - start) / 2;
+            if dx >= dy && dx >= dz {
+                boxes.select_nth_unstable_by_key(mid, |&(p, _)| p.0);
+            } else if dy >= dz {
+                boxes.select_nth_unstable_by_key(mid, |&(p, _)| p.1);
+            } else {
+                boxes.select_nth_unstable_by_key(mid, |&(p, _)| p.2);
+            }
+            let left = self.build(start, start + mid);
+            let right = self.build(start + mid, end);
+            self.nodes[node].children = Some((left, right));
+        }
+        node
+    }
+
+}
+
+impl Node {
+    /// Squared distances from `p` to the nearest and farthest point of the
+    /// node's bounding box
+    pub(crate) fn gaps(&self, p: Position) -> (i64, i64) {
+        let axis = |v: i64, lo: i64, hi: i64| {
+            let near = (lo - v).max(v - hi).max(0);
+            let far = (v - lo).max(hi - v);
+            (near * near, far * far)
+        };
+        let (nx, fx) = axis(p.0, self.min.0, self.max.0);
+        let (ny, fy) = axis(p.1, self.min.1, self.max.1);
+        let (nz, fz) = axis(p.2, self.min.2, self.max.2);
+        (nx + ny + nz, fx + fy + fz)
+    }
+}
@@ -0,0 +1,42 @@
+//! Advent of Code 2025 - Day 8: Playground
+//!
+//! Junction box parsing, closest-pair generation and the Union-Find that
+//! tracks circuits, shared by both parts. Pairs are streamed closest first
+//! from a spatial index instead of sorting all n·(n−1)/2 of them, and the
+//! spanning tree that completes the circuit is built without going through
+//! them at all.
+
+pub mod disjoint_set;
+pub mod export;
+mod kdtree;
+pub mod pairs;
+pub mod spanning;
+
+pub use disjoint_set::DisjointSet;
+pub use export::{write_components, write_dot, write_edges};
+pub use pairs::{NearestPairs, distance2};
+pub use spanning::spanning_tree;
+
+/// A junction box's (X, Y, Z) coordinates
+pub type Position = (i64, i64, i64);
+
+/// Parse one "X,Y,Z" box per line, skipping empty lines
+pub fn parse_positions(input: &str) -> Result<Vec<Position>, String> {
+    input
+        .lines()
+        .enumerate()
+        .filter(|(_, l)| !l.trim().is_empty())
+        .map(|(i, l)| {
+            let c: Vec<i64> = l
+                .trim()
+                .split(',')
+                .map(|s| s.trim().parse())
+                .collect::<Result<_, _>>()
+                .map_err(|e| format!("line {}: {}", i + 1, e))?;
+            match c[..] {
+                [x, y, z] => Ok((x, y, z)),
+                _ => Err(format!("line {}: expected X,Y,Z but found {} values", i + 1, c.len())),
+            }
+        })
+        .collect()
+}
@@ -1,57 +1,113 @@
+use playground::{DisjointSet, NearestPairs, Position, parse_positions};
+use std::collections::BTreeMap;
+use std::env;
 use std::fs;
+use std::process;
 
 /// Advent of Code 2025 - Day 8: Playground
 ///
 /// Connect the 1000 closest pairs of junction boxes using Union-Find,
 /// then multiply the sizes of the three largest circuits.
-
+///
+/// Usage: playground [--connections N] [--top K] [--sizes] [--largest-above X]
+///
+/// `--connections` and `--top` replace the 1000 pairs and the three circuits.
+/// `--sizes` prints how many circuits there are of each size after those
+/// connections. `--largest-above X` keeps connecting pairs, closest first,
+/// and reports after how many the largest circuit first has more than X boxes.
 fn main() {
+    let mut connections = 1000;
+    let mut top = 3;
+    let mut show_sizes = false;
+    let mut largest_above: Option<usize> = None;
+    let mut args = env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--connections" => connections = number(&arg, args.next()),
+            "--top" => top = number(&arg, args.next()),
+            "--sizes" => show_sizes = true,
+            "--largest-above" => largest_above = Some(number(&arg, args.next())),
+            _ => {
+                eprintln!("unknown argument '{}'", arg);
+                process::exit(2);
+            }
+        }
+    }
+
     let input = fs::read_to_string("input.txt").unwrap();
     
     // Parse positions
-    let pos: Vec<(i64, i64, i64)> = input
-        .lines()
-        .filter(|l| !l.is_empty())
-        .map(|l| {
-            let c: Vec<i64> = l.split(',').map(|s| s.parse().unwrap()).collect();
-            (c[0], c[1], c[2])
-        })
-        .collect();
+    let pos = parse_positions(&input).unwrap_or_else(|e| {
+        eprintln!("Error: {}", e);
+        process::exit(1);
+    });
     
-    let n = pos.len();
+    // Connect the closest pairs; a pair already in one circuit still counts
+    let mut s = circuit_sizes(&pos, connections);
     
-    // Generate all pairs sorted by distance
-    let mut pairs: Vec<(i64, usize, usize)> = Vec::new();
-    for i in 0..n {
-        for j in i+1..n {
-            let (dx, dy, dz) = (pos[i].0 - pos[j].0, pos[i].1 - pos[j].1, pos[i].2 - pos[j].2);
-            pairs.push((dx*dx + dy*dy + dz*dz, i, j));
-        }
+    if show_sizes {
+        print_distribution(&s, connections);
     }
-    pairs.sort_by_key(|p| p.0);
     
-    // Union-Find with path compression
-    let mut parent: Vec<usize> = (0..n).collect();
-    fn find(parent: &mut [usize], x: usize) -> usize {
-        if parent[x] != x { parent[x] = find(parent, parent[x]); }
-        parent[x]
+    // Get product of the largest circuits
+    s.sort_by(|a, b| b.cmp(a));
+    let label = if top == 3 { "three".to_string() } else { top.to_string() };
+    match s.iter().take(top).try_fold(1u128, |acc, &v| acc.checked_mul(v as u128)) {
+        Some(product) => println!("Product of {} largest circuit sizes: {}", label, product),
+        None => println!("Product of {} largest circuit sizes: too large for u128", label),
     }
-    
-    // Connect 1000 closest pairs
-    for &(_, i, j) in pairs.iter().take(1000) {
-        let (ri, rj) = (find(&mut parent, i), find(&mut parent, j));
-        if ri != rj { parent[ri] = rj; }
+
+    if let Some(x) = largest_above {
+        match connections_until_larger(&pos, x) {
+            Some(count) => println!("Largest circuit first exceeds {} boxes after {} connections", x, count),
+            None => println!("Largest circuit never exceeds {} boxes", x),
+        }
     }
-    
-    // Count circuit sizes
-    let mut sizes = std::collections::HashMap::new();
-    for i in 0..n { *sizes.entry(find(&mut parent, i)).or_insert(0) += 1; }
-    
-    // Get product of 3 largest
-    let mut s: Vec<u64> = sizes.values().map(|&v| v as u64).collect();
-    s.sort_by(|a, b| b.cmp(a));
-    
-    println!("Product of three largest circuit sizes: {}", s.iter().take(3).product::<u64>());
 }
 
+/// Parse the value of a numeric flag, or exit with a usage error
+fn number(flag: &str, value: Option<String>) -> usize {
+    value.and_then(|v| v.parse().ok()).unwrap_or_else(|| {
+        eprintln!("{} needs a non-negative number", flag);
+        process::exit(2);
+    })
+}
+
+/// Sizes of all circuits after connecting the `connections` closest pairs
+fn circuit_sizes(pos: &[Position], connections: usize) -> Vec<usize> {
+    let mut circuits = DisjointSet::new(pos.len());
+    for (_, i, j) in NearestPairs::new(pos).take(connections) {
+        circuits.union(i, j);
+    }
+    circuits.sizes()
+}
+
+/// Number of closest pairs to connect before some circuit holds more than
+/// `x` boxes, or `None` if even connecting every pair doesn't get there
+fn connections_until_larger(pos: &[Position], x: usize) -> Option<usize> {
+    if !pos.is_empty() && x == 0 {
+        return Some(0);
+    }
+    let mut circuits = DisjointSet::new(pos.len());
+    for (count, (_, i, j)) in NearestPairs::new(pos).enumerate() {
+        if circuits.union(i, j) && circuits.size(i) > x {
+            return Some(count + 1);
+        }
+    }
+    None
+}
+
+/// How many circuits there are of each size, largest first
+fn print_distribution(sizes: &[usize], connections: usize) {
+    let mut counts: BTreeMap<usize, usize> = BTreeMap::new();
+    for &s in sizes {
+        *counts.entry(s).or_insert(0) += 1;
+    }
 
+    println!("Circuit sizes after {} connections ({} circuits):", connections, sizes.len());
+    println!("{:>8}  {:>8}", "Size", "Circuits");
+    for (size, count) in counts.iter().rev() {
+        println!("{:>8}  {:>8}", size, count);
+    }
+    println!();
+}
@@ -0,0 +1,153 @@
+use crate::Position;
+use crate::kdtree::KdTree;
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
+
+/// Squared Euclidean distance between two boxes
+pub fn distance2(a: Position, b: Position) -> i64 {
+    let (dx, dy, dz) = (a.0 - b.0, a.1 - b.1, a.2 - b.2);
+    dx * dx + dy * dy + dz * dz
+}
+
+/// Every pair of boxes as `(distance², i, j)` with `i < j`, closest first and
+/// ties broken by `(i, j)`, exactly the order of sorting all pairs.
+///
+/// The boxes go into a k-d tree, and each pair belongs to whichever of its
+/// boxes comes first in the tree's order. Each box keeps a stream of the
+/// neighbours it owns in order of `(distance², j)`, filled in batches from the
+/// tree; a batch twice the size of the last is fetched whenever one runs out.
+/// A heap holds the head of every stream, so the next pair overall is always
+/// on top. Batches stop growing at `MAX_BATCH`, so memory is one heap entry
+/// per box plus at most twice the pairs taken so far, and never more than
+/// `MAX_BATCH` pairs per box, however the boxes are spread out.
+pub struct NearestPairs<'a> {
+    positions: &'a [Position],
+    tree: KdTree,
+    search: Search,
+    streams: Vec<Stream>,
+    /// The next pair `(distance², i, j, owner)` of every stream that has
+    /// one, with `i < j` whichever end owns it
+    heads: BinaryHeap<Reverse<(i64, usize, usize, usize)>>,
+}
+
+/// Largest batch a stream fetches at once
+const MAX_BATCH: usize = 256;
+
+/// A box's neighbours that haven't been handed out yet
+#[derive(Default)]
+struct Stream {
+    /// The current batch, in reverse so neighbours pop from the end
+    batch: Vec<(i64, usize)>,
+    /// Size asked for by the last fetch
+    asked: usize,
+    /// The last neighbour fetched; the next batch starts after it
+    last: Option<(i64, usize)>,
+    /// The last fetch found fewer neighbours than asked for
+    exhausted: bool,
+}
+
+impl<'a> NearestPairs<'a> {
+    pub fn new(positions: &'a [Position]) -> Self {
+        let mut pairs = NearestPairs {
+            positions,
+            tree: KdTree::new(positions),
+            search: Search::default(),
+            streams: (0..positions.len()).map(|_| Stream::default()).collect(),
+            heads: BinaryHeap::with_capacity(positions.len()),
+        };
+        for i in 0..positions.len() {
+            pairs.advance(i);
+        }
+        pairs
+    }
+
+    /// Push box i's next neighbour onto the heap, fetching a batch if needed.
+    ///
+    /// Sorting a stream by `(distance², j)` also sorts it by `(distance²,
+    /// min(i, j), max(i, j))`: at equal distance, neighbours below i come
+    /// first in order, then those above it.
+    fn advance(&mut self, i: usize) {
+        let stream = &mut self.streams[i];
+        if stream.batch.is_empty() && !stream.exhausted {
+            stream.asked = (stream.asked * 2).clamp(4, MAX_BATCH);
+            stream.batch = nearest(&self.tree, self.positions[i], i, stream.last, stream.asked, &mut self.search);
+            stream.exhausted = stream.batch.len() < stream.asked;
+            stream.last = stream.batch.first().copied();
+        }
+        if let Some((d, j)) = stream.batch.pop() {
+            self.heads.push(Reverse((d, i.min(j), i.max(j), i)));
+        }
+    }
+}
+
+impl Iterator for NearestPairs<'_> {
+    type Item = (i64, usize, usize);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let Reverse((d, i, j, owner)) = self.heads.pop()?;
+        self.advance(owner);
+        Some((d, i, j))
+    }
+}
+
+/// Buffers reused from one search to the next
+#[derive(Default)]
+struct Search {
+    stack: Vec<usize>,
+    best: BinaryHeap<(i64, usize)>,
+}
+
+/// Up to `k` boxes after box i in tree order nearest to it (at `p`) that
+/// come after `after` in `(distance², j)` order, farthest first.
+///
+/// Nodes are skipped when every box in them is too far to beat the k found
+/// so far, already fetched (closer than `after`), or not after box i in
+/// tree order. The last check drops whole subtrees, since a node's boxes
+/// are a contiguous run of that order.
+fn nearest(tree: &KdTree, p: Position, i: usize, after: Option<(i64, usize)>, k: usize, search: &mut Search) -> Vec<(i64, usize)> {
+    let own = tree.slot[i];
+    let floor = after.map_or(0, |(d, _)| d);
+    let Search { stack, best } = search;
+    best.clear();
+    stack.clear();
+    if !tree.nodes.is_empty() {
+        stack.push(0);
+    }
+
+    while let Some(n) = stack.pop() {
+        let node = &tree.nodes[n];
+        let (near, far) = node.gaps(p);
+        if node.end <= own + 1 || far < floor || (best.len() == k && best.peek().is_some_and(|&(d, _)| near > d)) {
+            continue;
+        }
+
+        match node.children {
+            // Visit the nearer child first so `best` tightens sooner
+            Some((left, right)) => {
+                let (l, r) = (tree.nodes[left].gaps(p).0, tree.nodes[right].gaps(p).0);
+                if l <= r {
+                    stack.extend([right, left]);
+                } else {
+                    stack.extend([left, right]);
+                }
+            }
+            None => {
+                for &(q, j) in &tree.boxes[node.start.max(own + 1)..node.end] {
+                    let key = (distance2(p, q), j);
+                    if after.is_some_and(|after| key <= after) {
+                        continue;
+                    }
+                    if best.len() < k {
+                        best.push(key);
+                    } else if best.peek().is_some_and(|&worst| key < worst) {
+                        best.pop();
+                        best.push(key);
+                    }
+                }
+            }
+        }
+    }
+    let mut found: Vec<(i64, usize)> = best.drain().collect();
+    found.sort_unstable_by(|a, b| b.cmp(a));
+    found
+}
@@ -0,0 +1,98 @@
+use crate::kdtree::KdTree;
+use crate::{DisjointSet, Position, distance2};
+
+/// The connections that join every box into one circuit when pairs are
+/// connected closest first, as `(distance², i, j)` in that order.
+///
+/// Pairs are totally ordered by `(distance², i, j)`, so this spanning tree is
+/// unique: it is what connecting `NearestPairs` one by one would keep, and its
+/// last edge is the connection that completes the circuit. Building it that
+/// way can mean going through almost every pair first (think of one box far
+/// away from all the others), so it is built with Borůvka's algorithm
+/// instead: every round, each circuit finds its closest pair to another
+/// circuit with a k-d tree search, and all of those are connected at once.
+/// That at least halves the number of circuits per round.
+pub fn spanning_tree(positions: &[Position]) -> Vec<(i64, usize, usize)> {
+    let n = positions.len();
+    let tree = KdTree::new(positions);
+    let mut circuits = DisjointSet::new(n);
+    let mut edges = Vec::with_capacity(n.saturating_sub(1));
+
+    // The circuit all of a node's boxes are in, if they share one
+    let mut label: Vec<Option<usize>> = vec![None; tree.nodes.len()];
+    // A lower bound on each box's distance² to any other circuit; circuits
+    // only grow, so a bound from an earlier round still holds
+    let mut reach = vec![0; n];
+    let mut stack = Vec::new();
+
+    while circuits.component_count() > 1 {
+        let root: Vec<usize> = (0..n).map(|i| circuits.find(i)).collect();
+
+        // Children come after their parent, so label bottom up
+        for k in (0..tree.nodes.len()).rev() {
+            let node = &tree.nodes[k];
+            label[k] = match node.children {
+                Some((left, right)) if label[left] == label[right] => label[left],
+                Some(_) => None,
+                None => {
+                    let first = root[tree.boxes[node.start].1];
+                    let same = tree.boxes[node.start..node.end].iter().all(|&(_, j)| root[j] == first);
+                    same.then_some(first)
+                }
+            };
+        }
+
+        // The closest pair leaving each circuit, indexed by its root. Boxes
+        // go in tree order, so neighbouring searches share a circuit's bound.
+        let mut best: Vec<Option<(i64, usize, usize)>> = vec![None; n];
+        for &(p, i) in &tree.boxes {
+            let own = root[i];
+            if best[own].is_some_and(|(d, _, _)| reach[i] > d) {
+                continue;
+            }
+            stack.clear();
+            stack.push(0);
+
+            while let Some(k) = stack.pop() {
+                let node = &tree.nodes[k];
+                let near = node.gaps(p).0;
+                if label[k] == Some(own) || best[own].is_some_and(|(d, _, _)| near > d) {
+                    continue;
+                }
+
+                match node.children {
+                    // Visit the nearer child first so `best` tightens sooner
+                    Some((left, right)) => {
+                        if tree.nodes[left].gaps(p).0 <= tree.nodes[right].gaps(p).0 {
+                            stack.extend([right, left]);
+                        } else {
+                            stack.extend([left, right]);
+                        }
+                    }
+                    None => {
+                        for &(q, j) in &tree.boxes[node.start..node.end] {
+                            let key = (distance2(p, q), i.min(j), i.max(j));
+                            if root[j] != own && best[own].is_none_or(|b| key < b) {
+                                best[own] = Some(key);
+                            }
+                        }
+                    }
+                }
+            }
+            // Nothing closer than the circuit's best was left unsearched
+            if let Some((d, _, _)) = best[own] {
+                reach[i] = d;
+            }
+        }
+
+        // Two circuits may pick the same pair; it is only connected once
+        for (d, i, j) in best.into_iter().flatten() {
+            if circuits.union(i, j) {
+                edges.push((d, i, j));
+            }
+        }
+    }
+
+    edges.sort_unstable();
+    edges
+}
@@ -1,3 +1,5 @@
+use forklift::{AccessRule, BitGrid};
+use std::env;
 use std::fs::File;
 use std::io::{self, BufRead, BufReader};
 
@@ -12,16 +14,38 @@ use std::io::{self, BufRead, BufReader};
 /// The '@' at position (0,2) has neighbors at 8 positions around it.
 /// Count how many of those neighbors are also '@'.
 /// If count < 4, the roll is accessible.
-
+///
+/// Usage: printing_department [--neighborhood moore|von-neumann] [--radius N]
+///                            [--offsets "dr,dc;..."] [--threshold N] [--wrap] [--packed]
+///
+/// `--packed` reads the map into a bit-packed grid and counts neighbors a whole
+/// word at a time, for maps too large to hold as characters.
 fn main() -> io::Result<()> {
+    // The neighborhood, threshold and wrapping can be changed from the command line
+    let (rule, rest) = AccessRule::from_args(env::args().skip(1))
+        .map_err(|e| io::Error::new(io::ErrorKind::InvalidInput, e))?;
+    let mut packed = false;
+    for arg in rest {
+        match arg.as_str() {
+            "--packed" => packed = true,
+            _ => return Err(io::Error::new(io::ErrorKind::InvalidInput, format!("unknown argument '{}'", arg))),
+        }
+    }
+
     // Read the grid from input file
     let file = File::open("input.txt")?;
     let reader = BufReader::new(file);
 
+    if packed {
+        let grid = BitGrid::read(reader)?;
+        println!("Accessible paper rolls: {}", grid.count_accessible(&rule));
+        return Ok(());
+    }
+
     // Parse the grid into a 2D vector of characters
     let grid: Vec<Vec<char>> = reader
         .lines()
-        .filter_map(|line| line.ok())
+        .map_while(Result::ok)
         .filter(|line| !line.is_empty())
         .map(|line| line.chars().collect())
         .collect();
@@ -29,13 +53,6 @@ fn main() -> io::Result<()> {
     let rows = grid.len();
     let cols = if rows > 0 { grid[0].len() } else { 0 };
 
-    // 8 directions: up, down, left, right, and 4 diagonals
-    let directions: [(i32, i32); 8] = [
-        (-1, -1), (-1, 0), (-1, 1),  // top-left, top, top-right
-        ( 0, -1),          ( 0, 1),  // left, right
-        ( 1, -1), ( 1, 0), ( 1, 1),  // bottom-left, bottom, bottom-right
-    ];
-
     let mut accessible_count = 0;
 
     // Check each cell in the grid
@@ -46,23 +63,13 @@ fn main() -> io::Result<()> {
                 continue;
             }
 
-            // Count adjacent paper rolls
-            let mut neighbor_rolls = 0;
-            for (dr, dc) in &directions {
-                let new_row = row as i32 + dr;
-                let new_col = col as i32 + dc;
-
-                // Check bounds
-                if new_row >= 0 && new_row < rows as i32 
-                   && new_col >= 0 && new_col < cols as i32 {
-                    if grid[new_row as usize][new_col as usize] == '@' {
-                        neighbor_rolls += 1;
-                    }
-                }
-            }
+            // Count adjacent paper rolls (out-of-bounds neighbors are skipped by the rule)
+            let neighbor_rolls = rule
+                .neighbors(rows, cols, row, col)
+                .filter(|&(r, c)| grid[r][c] == '@')
+                .count();
 
-            // Accessible if fewer than 4 adjacent rolls
-            if neighbor_rolls < 4 {
+            if rule.is_accessible(neighbor_rolls) {
                 accessible_count += 1;
             }
         }
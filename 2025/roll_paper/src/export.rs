@@ -0,0 +1,193 @@
+//! Frame-by-frame export of the removal cascade.
+//!
+//! Frame 0 is the starting grid and frame k is the grid after round k, so a
+//! cascade of n rounds produces n + 1 frames. In the image formats a removed
+//! roll keeps the color of the round it was removed in, which leaves the whole
+//! wave history visible in the last frame.
+
+use crate::Wave;
+use crate::gif::GifWriter;
+use std::fs::{self, File};
+use std::io::{self, BufWriter, Write};
+use std::path::Path;
+
+/// Binary netpbm flavors we can write
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ImageFormat {
+    /// P6: removed rolls colored by round
+    Ppm,
+    /// P5: removed rolls shaded by round
+    Pgm,
+}
+
+/// What a single cell looks like in a given frame
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Shade {
+    Empty,
+    Roll,
+    /// Removed in this round, at or before the frame being drawn
+    Removed(usize),
+}
+
+fn shade(wave: Wave, frame: usize) -> Shade {
+    match wave {
+        Wave::Empty => Shade::Empty,
+        Wave::Removed(round) if round <= frame => Shade::Removed(round),
+        Wave::Removed(_) | Wave::Never => Shade::Roll,
+    }
+}
+
+/// Zero-padded frame file name, wide enough to sort correctly
+fn frame_name(frame: usize, rounds: usize, extension: &str) -> String {
+    let width = rounds.to_string().len().max(3);
+    format!("frame_{:0width$}.{}", frame, extension, width = width)
+}
+
+/// Write one text file per frame: '@' for a roll, 'x' for a roll removed in
+/// that frame's round and '.' for an empty cell
+pub fn write_ascii_frames(dir: &Path, waves: &[Vec<Wave>], rounds: usize) -> io::Result<()> {
+    fs::create_dir_all(dir)?;
+
+    for frame in 0..=rounds {
+        let mut out = BufWriter::new(File::create(dir.join(frame_name(frame, rounds, "txt")))?);
+        for line in waves {
+            let row: String = line
+                .iter()
+                .map(|&wave| match shade(wave, frame) {
+                    Shade::Roll => '@',
+                    Shade::Removed(round) if round == frame => 'x',
+                    Shade::Removed(_) | Shade::Empty => '.',
+                })
+                .collect();
+            writeln!(out, "{}", row)?;
+        }
+        out.flush()?;
+    }
+    Ok(())
+}
+
+/// Write one PPM or PGM image per frame, each cell drawn as a `scale` x `scale` block
+pub fn write_images(dir: &Path, waves: &[Vec<Wave>], rounds: usize, format: ImageFormat, scale: usize) -> io::Result<()> {
+    fs::create_dir_all(dir)?;
+
+    let (width, height) = canvas(waves, scale);
+    let (magic, extension) = match format {
+        ImageFormat::Ppm => ("P6", "ppm"),
+        ImageFormat::Pgm => ("P5", "pgm"),
+    };
+
+    for frame in 0..=rounds {
+        let mut out = BufWriter::new(File::create(dir.join(frame_name(frame, rounds, extension)))?);
+        write!(out, "{}\n{} {}\n255\n", magic, width, height)?;
+
+        for line in waves {
+            let mut row = Vec::with_capacity(width * 3);
+            for &wave in line {
+                let shade = shade(wave, frame);
+                for _ in 0..scale {
+                    match format {
+                        ImageFormat::Ppm => row.extend_from_slice(&rgb(shade, rounds)),
+                        ImageFormat::Pgm => row.push(gray(shade, rounds)),
+                    }
+                }
+            }
+            for _ in 0..scale {
+                out.write_all(&row)?;
+            }
+        }
+        out.flush()?;
+    }
+    Ok(())
+}
+
+/// Write the whole cascade as a single looping GIF.
+/// `delay` is how long each frame is shown, in hundredths of a second.
+pub fn write_gif(path: &Path, waves: &[Vec<Wave>], rounds: usize, scale: usize, delay: u16) -> io::Result<()> {
+    let (width, height) = canvas(waves, scale);
+    if width > u16::MAX as usize || height > u16::MAX as usize {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!("{}x{} is too large for a GIF (max 65535 per side)", width, height),
+        ));
+    }
+
+    // Palette: 0 = empty, 1 = roll, 2..=255 = removal rounds spread over the gradient
+    let mut palette = [[0u8; 3]; 256];
+    palette[0] = rgb(Shade::Empty, rounds);
+    palette[1] = rgb(Shade::Roll, rounds);
+    for (i, entry) in palette.iter_mut().enumerate().skip(2) {
+        *entry = gradient((i - 2) as f64 / 253.0);
+    }
+    let index = |shade: Shade| -> u8 {
+        match shade {
+            Shade::Empty => 0,
+            Shade::Roll => 1,
+            Shade::Removed(round) => 2 + (position(round, rounds) * 253.0).round() as u8,
+        }
+    };
+
+    let file = BufWriter::new(File::create(path)?);
+    let mut gif = GifWriter::new(file, width as u16, height as u16, &palette, delay)?;
+
+    for frame in 0..=rounds {
+        let mut pixels = Vec::with_capacity(width * height);
+        for line in waves {
+            let row: Vec<u8> = line
+                .iter()
+                .flat_map(|&wave| std::iter::repeat_n(index(shade(wave, frame)), scale))
+                .collect();
+            for _ in 0..scale {
+                pixels.extend_from_slice(&row);
+            }
+        }
+        gif.frame(&pixels)?;
+    }
+
+    gif.finish()?;
+    Ok(())
+}
+
+/// Image size in pixels for the given grid and scale
+fn canvas(waves: &[Vec<Wave>], scale: usize) -> (usize, usize) {
+    let cols = waves.first().map_or(0, Vec::len);
+    (cols * scale, waves.len() * scale)
+}
+
+/// Where a round sits between the first (0.0) and last (1.0) round
+fn position(round: usize, rounds: usize) -> f64 {
+    if rounds <= 1 {
+        0.0
+    } else {
+        (round - 1) as f64 / (rounds - 1) as f64
+    }
+}
+
+fn rgb(shade: Shade, rounds: usize) -> [u8; 3] {
+    match shade {
+        Shade::Empty => [255, 255, 255],
+        Shade::Roll => [64, 64, 64],
+        Shade::Removed(round) => gradient(position(round, rounds)),
+    }
+}
+
+/// Early rounds are dark, late rounds light; remaining rolls are black
+fn gray(shade: Shade, rounds: usize) -> u8 {
+    match shade {
+        Shade::Empty => 255,
+        Shade::Roll => 0,
+        Shade::Removed(round) => (48.0 + 176.0 * position(round, rounds)).round() as u8,
+    }
+}
+
+/// Hue sweep from red (t = 0.0) through yellow and green to blue (t = 1.0)
+fn gradient(t: f64) -> [u8; 3] {
+    let hue = 240.0 * t.clamp(0.0, 1.0) / 60.0;
+    let x = 1.0 - (hue % 2.0 - 1.0).abs();
+    let (r, g, b) = match hue as u32 {
+        0 => (1.0, x, 0.0),
+        1 => (x, 1.0, 0.0),
+        2 => (0.0, 1.0, x),
+        _ => (0.0, x, 1.0),
+    };
+    [(r * 255.0) as u8, (g * 255.0) as u8, (b * 255.0) as u8]
+}
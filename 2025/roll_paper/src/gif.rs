@@ -0,0 +1,149 @@
+use std::collections::HashMap;
+use std::io::{self, Write};
+
+/// Minimal animated GIF89a writer.
+///
+/// Every frame is a full-size image drawn from one shared 256-entry palette,
+/// compressed with the LZW variant GIF requires. Only what the cascade
+/// animation needs is implemented: no transparency, no local palettes.
+pub struct GifWriter<W: Write> {
+    out: W,
+    width: u16,
+    height: u16,
+    delay: u16,
+}
+
+impl<W: Write> GifWriter<W> {
+    /// Write the header, palette and looping extension.
+    /// `delay` is the time each frame is shown, in hundredths of a second.
+    pub fn new(mut out: W, width: u16, height: u16, palette: &[[u8; 3]; 256], delay: u16) -> io::Result<Self> {
+        out.write_all(b"GIF89a")?;
+        out.write_all(&width.to_le_bytes())?;
+        out.write_all(&height.to_le_bytes())?;
+        // Global color table present, 8 bits of color resolution, 256 entries
+        out.write_all(&[0xF7, 0, 0])?;
+        for rgb in palette {
+            out.write_all(rgb)?;
+        }
+
+        // NETSCAPE2.0 application extension: loop forever
+        out.write_all(&[0x21, 0xFF, 0x0B])?;
+        out.write_all(b"NETSCAPE2.0")?;
+        out.write_all(&[0x03, 0x01, 0x00, 0x00, 0x00])?;
+
+        Ok(GifWriter { out, width, height, delay })
+    }
+
+    /// Append one frame given as row-major palette indices
+    pub fn frame(&mut self, pixels: &[u8]) -> io::Result<()> {
+        debug_assert_eq!(pixels.len(), self.width as usize * self.height as usize);
+
+        // Graphic control extension carrying the frame delay
+        self.out.write_all(&[0x21, 0xF9, 0x04, 0x00])?;
+        self.out.write_all(&self.delay.to_le_bytes())?;
+        self.out.write_all(&[0x00, 0x00])?;
+
+        // Image descriptor covering the whole canvas, no local color table
+        self.out.write_all(&[0x2C, 0, 0, 0, 0])?;
+        self.out.write_all(&self.width.to_le_bytes())?;
+        self.out.write_all(&self.height.to_le_bytes())?;
+        self.out.write_all(&[0x00])?;
+
+        // LZW minimum code size, then the data in sub-blocks of at most 255 bytes
+        self.out.write_all(&[MIN_CODE_SIZE])?;
+        for block in lzw_encode(pixels).chunks(255) {
+            self.out.write_all(&[block.len() as u8])?;
+            self.out.write_all(block)?;
+        }
+        self.out.write_all(&[0x00])
+    }
+
+    /// Write the trailer and hand back the underlying writer
+    pub fn finish(mut self) -> io::Result<W> {
+        self.out.write_all(&[0x3B])?;
+        self.out.flush()?;
+        Ok(self.out)
+    }
+}
+
+/// 8-bit palette indices, so codes start out 9 bits wide
+const MIN_CODE_SIZE: u8 = 8;
+
+/// GIF caps LZW codes at 12 bits
+const MAX_CODES: u16 = 4096;
+
+/// Compress palette indices with GIF's variable-width LZW
+fn lzw_encode(pixels: &[u8]) -> Vec<u8> {
+    let clear = 1u16 << MIN_CODE_SIZE;
+    let end = clear + 1;
+
+    let mut bits = BitWriter::default();
+    let mut table: HashMap<(u16, u8), u16> = HashMap::new();
+    let mut next_code = end + 1;
+    let mut code_size = MIN_CODE_SIZE + 1;
+
+    bits.write(clear, code_size);
+
+    let Some((&first, rest)) = pixels.split_first() else {
+        bits.write(end, code_size);
+        return bits.finish();
+    };
+
+    let mut prefix = first as u16;
+    for &pixel in rest {
+        if let Some(&code) = table.get(&(prefix, pixel)) {
+            prefix = code;
+            continue;
+        }
+
+        bits.write(prefix, code_size);
+
+        if next_code < MAX_CODES {
+            table.insert((prefix, pixel), next_code);
+            next_code += 1;
+            // The decoder widens one entry later than we add it, hence `>`
+            if next_code > (1 << code_size) && code_size < 12 {
+                code_size += 1;
+            }
+        } else {
+            // Table full: start over rather than keep emitting stale codes
+            bits.write(clear, code_size);
+            table.clear();
+            next_code = end + 1;
+            code_size = MIN_CODE_SIZE + 1;
+        }
+
+        prefix = pixel as u16;
+    }
+
+    bits.write(prefix, code_size);
+    bits.write(end, code_size);
+    bits.finish()
+}
+
+/// Packs variable-width codes least significant bit first
+#[derive(Default)]
+struct BitWriter {
+    bytes: Vec<u8>,
+    buffer: u32,
+    used: u8,
+}
+
+impl BitWriter {
+    fn write(&mut self, code: u16, width: u8) {
+        self.buffer |= (code as u32) << self.used;
+        self.used += width;
+        while self.used >= 8 {
+            self.bytes.push(self.buffer as u8);
+            self.buffer >>= 8;
+            self.used -= 8;
+        }
+    }
+
+    fn finish(mut self) -> Vec<u8> {
+        if self.used > 0 {
+            self.bytes.push(self.buffer as u8);
+        }
+        self.bytes
+    }
+}
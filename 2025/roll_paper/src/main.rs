@@ -1,84 +1,251 @@
+//! Advent of Code 2025 - Day 4: Printing Department (Part 2)
+//!
+//! Problem: Repeatedly remove accessible paper rolls until no more can be removed.
+//! A roll is accessible if it has fewer than 4 adjacent rolls (8 directions).
+//! When a roll is removed, its neighbors may become accessible.
+//!
+//! Strategy: Worklist cascade instead of rescanning the whole grid every round.
+//! - Count the neighbors of every roll once up front
+//! - The first round's queue is every roll that is already accessible
+//! - Removing a roll decrements its neighbors' counts; a neighbor whose count
+//!   just dropped below the threshold joins the next round's queue
+//!
+//! Each roll enters the queue at most once and each removal touches a fixed number
+//! of cells (8 with the default rule), so the whole cascade is O(cells)
+//! regardless of how many rounds it takes.
+//!
+//! Usage: roll_paper [--neighborhood moore|von-neumann] [--radius N]
+//!                   [--offsets "dr,dc;..."] [--threshold N] [--wrap] [--waves]
+//!                   [--frames DIR] [--ppm DIR] [--pgm DIR] [--gif FILE]
+//!                   [--scale N] [--delay CENTISECONDS] [--packed]
+//!
+//! `--waves` also prints the round in which every roll was removed, as a grid
+//! and as a per-round count table, to show which regions clear first and which
+//! core never does.
+//!
+//! The export flags write one frame per removal round: ASCII frames, binary
+//! PPM/PGM images with removed rolls colored by round, or a single animated GIF.
+//! `--scale` sets the pixels per cell for images (default 4) and `--delay` the
+//! GIF frame time (default 10 = 0.1s).
+//!
+//! `--packed` runs the cascade on a bit-packed grid instead, for maps too large
+//! to hold as characters. It only reports the total, so it can't be combined
+//! with the wave map or the exports, which need every cell's round.
+
+mod export;
+mod gif;
+
+use export::ImageFormat;
+use forklift::{AccessRule, BitGrid};
+use std::env;
 use std::fs::File;
 use std::io::{self, BufRead, BufReader};
+use std::path::PathBuf;
+
+/// Count adjacent paper rolls for every position in the grid
+fn count_neighbors(grid: &[Vec<char>], rule: &AccessRule) -> Vec<Vec<usize>> {
+    let rows = grid.len();
+    let cols = if rows > 0 { grid[0].len() } else { 0 };
+    let mut counts = vec![vec![0; cols]; rows];
+
+    for (row, line) in counts.iter_mut().enumerate() {
+        for (col, count) in line.iter_mut().enumerate() {
+            *count = rule
+                .neighbors(rows, cols, row, col)
+                .filter(|&(r, c)| grid[r][c] == '@')
+                .count();
+        }
+    }
+    counts
+}
 
-/// Advent of Code 2025 - Day 4: Printing Department (Part 2)
-///
-/// Problem: Repeatedly remove accessible paper rolls until no more can be removed.
-/// A roll is accessible if it has fewer than 4 adjacent rolls (8 directions).
-/// When a roll is removed, its neighbors may become accessible.
-
-/// 8 directions: up, down, left, right, and 4 diagonals
-const DIRECTIONS: [(i32, i32); 8] = [
-    (-1, -1), (-1, 0), (-1, 1),
-    ( 0, -1),          ( 0, 1),
-    ( 1, -1), ( 1, 0), ( 1, 1),
-];
-
-/// Count adjacent paper rolls for a given position
-fn count_neighbors(grid: &[Vec<char>], row: usize, col: usize) -> usize {
-    let rows = grid.len() as i32;
-    let cols = grid[0].len() as i32;
-    let mut count = 0;
-
-    for (dr, dc) in &DIRECTIONS {
-        let new_row = row as i32 + dr;
-        let new_col = col as i32 + dc;
-
-        if new_row >= 0 && new_row < rows && new_col >= 0 && new_col < cols {
-            if grid[new_row as usize][new_col as usize] == '@' {
-                count += 1;
+/// Remove accessible rolls round by round until none are left.
+/// Returns the positions removed in each round, first round first.
+fn cascade(grid: &mut [Vec<char>], rule: &AccessRule) -> Vec<Vec<(usize, usize)>> {
+    let rows = grid.len();
+    let cols = if rows > 0 { grid[0].len() } else { 0 };
+    let mut counts = count_neighbors(grid, rule);
+
+    // queued[r][c] is set once a roll has been scheduled for removal,
+    // so it can never be pushed a second time
+    let mut queued = vec![vec![false; cols]; rows];
+    let mut queue: Vec<(usize, usize)> = Vec::new();
+
+    for row in 0..rows {
+        for col in 0..cols {
+            if grid[row][col] == '@' && rule.is_accessible(counts[row][col]) {
+                queued[row][col] = true;
+                queue.push((row, col));
             }
         }
     }
-    count
-}
 
-/// Find all accessible rolls (fewer than 4 neighbors)
-fn find_accessible(grid: &[Vec<char>]) -> Vec<(usize, usize)> {
-    let mut accessible = Vec::new();
+    let mut rounds = Vec::new();
 
-    for row in 0..grid.len() {
-        for col in 0..grid[0].len() {
-            if grid[row][col] == '@' && count_neighbors(grid, row, col) < 4 {
-                accessible.push((row, col));
+    while !queue.is_empty() {
+        // Everything in the queue is removed simultaneously, so clear the
+        // whole round from the grid before updating any neighbor counts
+        for &(row, col) in &queue {
+            grid[row][col] = '.';
+        }
+
+        // Only the cells that count a removed roll as their neighbor lose one;
+        // for a custom offset mask these are not the removed roll's own neighbors
+        let mut next = Vec::new();
+        for &(row, col) in &queue {
+            for (r, c) in rule.dependents(rows, cols, row, col) {
+                counts[r][c] -= 1;
+                if grid[r][c] == '@' && !queued[r][c] && rule.is_accessible(counts[r][c]) {
+                    queued[r][c] = true;
+                    next.push((r, c));
+                }
             }
         }
+
+        rounds.push(std::mem::replace(&mut queue, next));
+    }
+
+    rounds
+}
+
+/// What happened to a single cell during the cascade
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Wave {
+    /// The cell never held a roll
+    Empty,
+    /// The roll was removed in this round (1-based)
+    Removed(usize),
+    /// The roll is still there once the cascade stops
+    Never,
+}
+
+/// Label every cell with the round its roll was removed in.
+/// `grid` is the grid after the cascade, so any '@' left in it was never removed.
+fn wave_map(grid: &[Vec<char>], rounds: &[Vec<(usize, usize)>]) -> Vec<Vec<Wave>> {
+    let mut waves: Vec<Vec<Wave>> = grid
+        .iter()
+        .map(|line| line.iter().map(|&ch| if ch == '@' { Wave::Never } else { Wave::Empty }).collect())
+        .collect();
+
+    for (round, removed) in rounds.iter().enumerate() {
+        for &(row, col) in removed {
+            waves[row][col] = Wave::Removed(round + 1);
+        }
     }
-    accessible
+    waves
+}
+
+/// Print the wave map and a per-round count table.
+///
+/// Each cell shows the round its roll was removed in, `#` for a roll that is
+/// never removed and `.` for a cell that never held a roll. Cells are padded
+/// to the width of the last round so columns stay aligned.
+fn print_waves(grid: &[Vec<char>], rounds: &[Vec<(usize, usize)>]) {
+    let waves = wave_map(grid, rounds);
+    let width = rounds.len().max(1).to_string().len();
+
+    println!("Removal waves (# = never removed, . = empty):");
+    for line in &waves {
+        let cells: Vec<String> = line
+            .iter()
+            .map(|cell| match cell {
+                Wave::Removed(round) => format!("{:>width$}", round, width = width),
+                Wave::Never => format!("{:>width$}", '#', width = width),
+                Wave::Empty => format!("{:>width$}", '.', width = width),
+            })
+            .collect();
+        println!("{}", cells.join(" "));
+    }
+
+    let stuck = grid.iter().flatten().filter(|&&ch| ch == '@').count();
+    let mut remaining = stuck + rounds.iter().map(Vec::len).sum::<usize>();
+
+    println!();
+    println!("{:>5}  {:>8}  {:>9}", "Round", "Removed", "Remaining");
+    for (round, removed) in rounds.iter().enumerate() {
+        remaining -= removed.len();
+        println!("{:>5}  {:>8}  {:>9}", round + 1, removed.len(), remaining);
+    }
+    println!("{:>5}  {:>8}  {:>9}", "never", stuck, stuck);
+    println!();
 }
 
 fn main() -> io::Result<()> {
+    // The neighborhood, threshold and wrapping can be changed from the command line
+    let (rule, rest) = AccessRule::from_args(env::args().skip(1))
+        .map_err(|e| io::Error::new(io::ErrorKind::InvalidInput, e))?;
+    let invalid = |msg: String| io::Error::new(io::ErrorKind::InvalidInput, msg);
+
+    let mut show_waves = false;
+    let mut packed = false;
+    let mut frames_dir: Option<PathBuf> = None;
+    let mut images: Vec<(ImageFormat, PathBuf)> = Vec::new();
+    let mut gif_path: Option<PathBuf> = None;
+    let mut scale: usize = 4;
+    let mut delay: u16 = 10;
+
+    let mut rest = rest.into_iter();
+    while let Some(arg) = rest.next() {
+        let mut value = || rest.next().ok_or_else(|| invalid(format!("{} needs a value", arg)));
+        match arg.as_str() {
+            "--waves" => show_waves = true,
+            "--packed" => packed = true,
+            "--frames" => frames_dir = Some(value()?.into()),
+            "--ppm" => images.push((ImageFormat::Ppm, value()?.into())),
+            "--pgm" => images.push((ImageFormat::Pgm, value()?.into())),
+            "--gif" => gif_path = Some(value()?.into()),
+            "--scale" => {
+                scale = value()?.parse().map_err(|_| invalid("--scale expects a positive integer".to_string()))?;
+                if scale == 0 {
+                    return Err(invalid("--scale must be at least 1".to_string()));
+                }
+            }
+            "--delay" => delay = value()?.parse().map_err(|_| invalid("--delay expects hundredths of a second".to_string()))?,
+            _ => return Err(invalid(format!("unknown argument '{}'", arg))),
+        }
+    }
+
     // Read the grid from input file
     let file = File::open("input.txt")?;
     let reader = BufReader::new(file);
 
+    if packed {
+        if show_waves || frames_dir.is_some() || !images.is_empty() || gif_path.is_some() {
+            return Err(invalid("--packed can't be combined with --waves or the export flags".to_string()));
+        }
+        let mut grid = BitGrid::read(reader)?;
+        let total_removed: usize = grid.cascade(&rule).iter().sum();
+        println!("Total rolls removed: {}", total_removed);
+        return Ok(());
+    }
+
     // Parse the grid into a 2D vector of characters
     let mut grid: Vec<Vec<char>> = reader
         .lines()
-        .filter_map(|line| line.ok())
+        .map_while(Result::ok)
         .filter(|line| !line.is_empty())
         .map(|line| line.chars().collect())
         .collect();
 
-    let mut total_removed = 0;
-
     // Keep removing accessible rolls until none are left
-    loop {
-        let accessible = find_accessible(&grid);
+    let rounds = cascade(&mut grid, &rule);
+    let total_removed: usize = rounds.iter().map(Vec::len).sum();
 
-        if accessible.is_empty() {
-            break; // No more rolls can be removed
-        }
-
-        // Remove all accessible rolls
-        for (row, col) in &accessible {
-            grid[*row][*col] = '.';
-        }
+    if show_waves {
+        print_waves(&grid, &rounds);
+    }
 
-        total_removed += accessible.len();
+    let waves = wave_map(&grid, &rounds);
+    if let Some(dir) = &frames_dir {
+        export::write_ascii_frames(dir, &waves, rounds.len())?;
+    }
+    for (format, dir) in &images {
+        export::write_images(dir, &waves, rounds.len(), *format, scale)?;
+    }
+    if let Some(path) = &gif_path {
+        export::write_gif(path, &waves, rounds.len(), scale, delay)?;
     }
 
     println!("Total rolls removed: {}", total_removed);
     Ok(())
 }
-
@@ -0,0 +1,132 @@
+//! Advent of Code 2025 - shared parser for blank-line-separated inputs
+//!
+//! Several puzzles (Day 5's ingredient database, Day 12's shapes and regions)
+//! put their input in sections separated by a blank line. Splitting on the
+//! literal "\n\n" breaks on CRLF files and on separator lines holding stray
+//! spaces, and indexing the result panics when a section is missing. This
+//! parser handles all three and reports a missing section by name.
+
+use std::error::Error;
+use std::fmt;
+
+/// One block of consecutive non-blank lines
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Section {
+    pub name: String,
+    /// 1-based line number of the section's first line in the original input
+    pub first_line: usize,
+    /// The section's lines with line endings removed
+    pub lines: Vec<String>,
+}
+
+impl Section {
+    /// The section's lines joined with '\n'
+    pub fn text(&self) -> String {
+        self.lines.join("\n")
+    }
+}
+
+/// A required section was not in the input
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SectionError {
+    pub missing: String,
+    pub found: Vec<String>,
+}
+
+impl fmt::Display for SectionError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.found.is_empty() {
+            write!(f, "missing required section '{}' (the input is empty)", self.missing)
+        } else {
+            write!(f, "missing required section '{}' (found: {})", self.missing, self.found.join(", "))
+        }
+    }
+}
+
+impl Error for SectionError {}
+
+/// An input split into named sections
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Sections {
+    sections: Vec<Section>,
+}
+
+impl Sections {
+    /// Split `input` into sections.
+    ///
+    /// "\r\n" and lone "\r" both count as line endings. Any line that is empty
+    /// or whitespace-only separates sections, runs of them count as one, and
+    /// blank lines at the start or end are ignored. Sections are named
+    /// "section 1", "section 2", ... until renamed.
+    pub fn parse(input: &str) -> Self {
+        let normalized = input.replace("\r\n", "\n").replace('\r', "\n");
+        let mut sections: Vec<Section> = Vec::new();
+        let mut current: Option<Section> = None;
+
+        for (i, line) in normalized.split('\n').enumerate() {
+            if line.trim().is_empty() {
+                sections.extend(current.take());
+                continue;
+            }
+
+            current
+                .get_or_insert_with(|| Section {
+                    name: format!("section {}", sections.len() + 1),
+                    first_line: i + 1,
+                    lines: Vec::new(),
+                })
+                .lines
+                .push(line.to_string());
+        }
+        sections.extend(current);
+
+        Sections { sections }
+    }
+
+    /// Name the sections in order. Sections beyond `names` keep their default name.
+    pub fn with_names(mut self, names: &[&str]) -> Self {
+        for (section, name) in self.sections.iter_mut().zip(names) {
+            section.name = name.to_string();
+        }
+        self
+    }
+
+    /// Rename every section for which `name` returns a name, based on its contents
+    pub fn name_by(mut self, name: impl Fn(&Section) -> Option<String>) -> Self {
+        for section in &mut self.sections {
+            if let Some(new_name) = name(section) {
+                section.name = new_name;
+            }
+        }
+        self
+    }
+
+    /// The first section with this name
+    pub fn get(&self, name: &str) -> Result<&Section, SectionError> {
+        self.sections.iter().find(|s| s.name == name).ok_or_else(|| SectionError {
+            missing: name.to_string(),
+            found: self.sections.iter().map(|s| s.name.clone()).collect(),
+        })
+    }
+
+    pub fn iter(&self) -> std::slice::Iter<'_, Section> {
+        self.sections.iter()
+    }
+
+    pub fn len(&self) -> usize {
+        self.sections.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.sections.is_empty()
+    }
+}
+
+impl<'a> IntoIterator for &'a Sections {
+    type Item = &'a Section;
+    type IntoIter = std::slice::Iter<'a, Section>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.sections.iter()
+    }
+}
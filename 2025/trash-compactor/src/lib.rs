@@ -0,0 +1,70 @@
+//! Advent of Code 2025 - Day 6: Trash Compactor
+//!
+//! Shared worksheet parsing for both reading modes. A worksheet is split into
+//! problem blocks once; each binary then decides how to read the digits.
+
+pub mod number;
+pub mod operator;
+pub mod options;
+pub mod reading;
+pub mod render;
+pub mod worksheet;
+
+use bignum::BigUint;
+use std::env;
+use std::fs;
+use std::io;
+
+pub use number::Number;
+pub use operator::{EvalError, Fold, Operator};
+pub use options::Options;
+pub use reading::ReadingDirection;
+pub use render::print_blocks;
+pub use worksheet::{Block, BlockError, Worksheet};
+
+/// Solve `input.txt` with the command line flags, reading each block in
+/// `default` unless `--read` says otherwise. Both binaries are this function.
+///
+/// Flags: [--read rows|columns-rtl|columns-ltr|bottom-up]
+///        [--fold left|right] [--fold N=left|right] [--exact] [--verbose]
+///
+/// `--read` picks another reading direction, so other cephalopod dialects can
+/// be solved without a new binary.
+///
+/// Operators: + * - / ^ min max. Division must be exact and an unknown
+/// operator is an error. `--fold right` groups operands as a op (b op c)
+/// instead of (a op b) op c; `--fold N=right` does so for block N only.
+///
+/// Arithmetic is checked `u128`: a block that overflows is reported by
+/// number and column span. `--exact` uses big integers instead, so any
+/// worksheet gets an exact grand total.
+///
+/// `--verbose` prints every block first: its column span, its rows as cut
+/// from the worksheet, the operands in reading order, operator and result.
+pub fn run(default: ReadingDirection) -> io::Result<()> {
+    let options = Options::from_args(env::args().skip(1), default)
+        .map_err(|e| io::Error::new(io::ErrorKind::InvalidInput, e))?;
+
+    let input = fs::read_to_string("input.txt")?;
+
+    // Split the worksheet into problem blocks (column span, operand cells, operator),
+    // then read and evaluate every block in the chosen direction
+    let worksheet = Worksheet::parse(&input);
+    if options.verbose {
+        if options.exact {
+            print_blocks::<BigUint>(&worksheet, &options);
+        } else {
+            print_blocks::<u128>(&worksheet, &options);
+        }
+    }
+
+    let grand_total = if options.exact {
+        worksheet.grand_total::<BigUint>(&options).map(|total| total.to_string())
+    } else {
+        worksheet.grand_total::<u128>(&options).map(|total| total.to_string())
+    }
+    .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e.to_string()))?;
+
+    println!("Grand total: {}", grand_total);
+    Ok(())
+}
@@ -0,0 +1,95 @@
+use bignum::BigUint;
+use std::fmt::Display;
+use std::str::FromStr;
+
+/// Largest result, in bits, an exact `^` may produce before it is refused
+const MAX_POWER_BITS: u64 = 1 << 24;
+
+/// Integer types a worksheet can be evaluated in.
+///
+/// `u128` is fast but every operation is checked, so a block that outgrows it
+/// is reported instead of wrapping or panicking. `BigUint` never overflows.
+pub trait Number: Clone + Ord + Display + FromStr {
+    /// Short name used in error messages
+    const NAME: &'static str;
+
+    fn zero() -> Self;
+    fn one() -> Self;
+    fn checked_add(&self, other: &Self) -> Option<Self>;
+    fn checked_mul(&self, other: &Self) -> Option<Self>;
+    /// `None` if the result would be negative
+    fn checked_sub(&self, other: &Self) -> Option<Self>;
+    /// Quotient and remainder, `None` when dividing by zero
+    fn div_rem(&self, other: &Self) -> Option<(Self, Self)>;
+    fn checked_pow(&self, exp: &Self) -> Option<Self>;
+}
+
+impl Number for u128 {
+    const NAME: &'static str = "u128";
+
+    fn zero() -> Self {
+        0
+    }
+
+    fn one() -> Self {
+        1
+    }
+
+    fn checked_add(&self, other: &Self) -> Option<Self> {
+        u128::checked_add(*self, *other)
+    }
+
+    fn checked_mul(&self, other: &Self) -> Option<Self> {
+        u128::checked_mul(*self, *other)
+    }
+
+    fn checked_sub(&self, other: &Self) -> Option<Self> {
+        u128::checked_sub(*self, *other)
+    }
+
+    fn div_rem(&self, other: &Self) -> Option<(Self, Self)> {
+        (*other != 0).then(|| (self / other, self % other))
+    }
+
+    fn checked_pow(&self, exp: &Self) -> Option<Self> {
+        u32::try_from(*exp).ok().and_then(|exp| u128::checked_pow(*self, exp))
+    }
+}
+
+impl Number for BigUint {
+    const NAME: &'static str = "big integers";
+
+    fn zero() -> Self {
+        BigUint::zero()
+    }
+
+    fn one() -> Self {
+        BigUint::one()
+    }
+
+    fn checked_add(&self, other: &Self) -> Option<Self> {
+        Some(self + other)
+    }
+
+    fn checked_mul(&self, other: &Self) -> Option<Self> {
+        Some(self * other)
+    }
+
+    fn checked_sub(&self, other: &Self) -> Option<Self> {
+        BigUint::checked_sub(self, other)
+    }
+
+    fn div_rem(&self, other: &Self) -> Option<(Self, Self)> {
+        BigUint::div_rem(self, other)
+    }
+
+    /// Refuses results over `MAX_POWER_BITS` bits so a stray `9 ^ 999999999`
+    /// can't exhaust memory
+    fn checked_pow(&self, exp: &Self) -> Option<Self> {
+        let exp = u32::try_from(exp.to_u128()?).ok()?;
+        if self.bits().saturating_mul(exp as u64) > MAX_POWER_BITS {
+            return None;
+        }
+        Some(self.pow(exp))
+    }
+}
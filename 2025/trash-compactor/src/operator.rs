@@ -0,0 +1,167 @@
+use crate::Number;
+use std::fmt;
+use std::str::FromStr;
+
+/// The operation written under a problem block
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Operator {
+    Add,
+    Multiply,
+    Subtract,
+    /// Exact integer division; a remainder is an error
+    Divide,
+    Power,
+    Min,
+    Max,
+}
+
+impl Operator {
+    pub fn symbol(self) -> &'static str {
+        match self {
+            Operator::Add => "+",
+            Operator::Multiply => "*",
+            Operator::Subtract => "-",
+            Operator::Divide => "/",
+            Operator::Power => "^",
+            Operator::Min => "min",
+            Operator::Max => "max",
+        }
+    }
+
+    /// Combine two operands
+    fn apply<N: Number>(self, a: N, b: N) -> Result<N, EvalError> {
+        let overflow = || EvalError::Overflow { expression: format!("{} {} {}", a, self, b), number: N::NAME };
+        match self {
+            Operator::Add => a.checked_add(&b).ok_or_else(overflow),
+            Operator::Multiply => a.checked_mul(&b).ok_or_else(overflow),
+            Operator::Subtract => a.checked_sub(&b).ok_or_else(|| EvalError::Negative(format!("{} - {}", a, b))),
+            Operator::Divide => match a.div_rem(&b) {
+                None => Err(EvalError::DivisionByZero(format!("{} / {}", a, b))),
+                Some((_, remainder)) if remainder != N::zero() => Err(EvalError::Remainder {
+                    expression: format!("{} / {}", a, b),
+                    remainder: remainder.to_string(),
+                }),
+                Some((quotient, _)) => Ok(quotient),
+            },
+            Operator::Power => a.checked_pow(&b).ok_or_else(overflow),
+            Operator::Min => Ok(a.min(b)),
+            Operator::Max => Ok(a.max(b)),
+        }
+    }
+
+    /// Result of applying the operator to no operands at all, if it has one
+    fn identity<N: Number>(self) -> Option<N> {
+        match self {
+            Operator::Add => Some(N::zero()),
+            Operator::Multiply => Some(N::one()),
+            _ => None,
+        }
+    }
+
+    /// Reduce the operands in order with the given fold.
+    ///
+    /// `Left` computes ((a op b) op c), `Right` computes a op (b op c); the two
+    /// only differ for `-`, `/` and `^`.
+    pub fn evaluate<N: Number>(self, fold: Fold, operands: &[N]) -> Result<N, EvalError> {
+        let Some((first, rest)) = operands.split_first() else {
+            return self.identity().ok_or(EvalError::NoOperands);
+        };
+
+        match fold {
+            Fold::Left => rest.iter().try_fold(first.clone(), |acc, x| self.apply(acc, x.clone())),
+            Fold::Right => {
+                let (last, init) = operands.split_last().unwrap();
+                init.iter().rev().try_fold(last.clone(), |acc, x| self.apply(x.clone(), acc))
+            }
+        }
+    }
+}
+
+impl FromStr for Operator {
+    type Err = EvalError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "+" => Ok(Operator::Add),
+            "*" => Ok(Operator::Multiply),
+            "-" => Ok(Operator::Subtract),
+            "/" => Ok(Operator::Divide),
+            "^" => Ok(Operator::Power),
+            "min" => Ok(Operator::Min),
+            "max" => Ok(Operator::Max),
+            "" => Err(EvalError::MissingOperator),
+            _ => Err(EvalError::UnknownOperator(s.to_string())),
+        }
+    }
+}
+
+impl fmt::Display for Operator {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.symbol())
+    }
+}
+
+/// Which way a block's operands are grouped
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Fold {
+    #[default]
+    Left,
+    Right,
+}
+
+impl fmt::Display for Fold {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Fold::Left => write!(f, "left"),
+            Fold::Right => write!(f, "right"),
+        }
+    }
+}
+
+impl FromStr for Fold {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "left" => Ok(Fold::Left),
+            "right" => Ok(Fold::Right),
+            _ => Err(format!("unknown fold '{}' (expected left or right)", s)),
+        }
+    }
+}
+
+/// Why a block could not be evaluated
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum EvalError {
+    MissingOperator,
+    UnknownOperator(String),
+    /// An operand that isn't a plain decimal number
+    BadOperand(String),
+    /// `-`, `/`, `^`, `min` and `max` need at least one operand
+    NoOperands,
+    Negative(String),
+    DivisionByZero(String),
+    Remainder { expression: String, remainder: String },
+    /// The result doesn't fit the number type (or, for `^` on big integers,
+    /// would be unreasonably large)
+    Overflow { expression: String, number: &'static str },
+}
+
+impl fmt::Display for EvalError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            EvalError::MissingOperator => write!(f, "no operator below the block"),
+            EvalError::UnknownOperator(op) => write!(f, "unknown operator '{}'", op),
+            EvalError::BadOperand(op) => write!(f, "'{}' is not a number", op),
+            EvalError::NoOperands => write!(f, "no operands to apply the operator to"),
+            EvalError::Negative(expression) => write!(f, "{} is negative", expression),
+            EvalError::DivisionByZero(expression) => write!(f, "{} divides by zero", expression),
+            EvalError::Remainder { expression, remainder } => {
+                write!(f, "{} leaves a remainder of {}", expression, remainder)
+            }
+            EvalError::Overflow { expression, number } => write!(f, "{} is too large for {}", expression, number),
+        }
+    }
+}
+
+impl std::error::Error for EvalError {}
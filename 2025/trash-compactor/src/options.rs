@@ -0,0 +1,58 @@
+use crate::{Fold, ReadingDirection};
+use std::collections::HashMap;
+
+/// Command line settings shared by both Trash Compactor binaries
+#[derive(Debug, Clone)]
+pub struct Options {
+    pub direction: ReadingDirection,
+    /// Fold used for every block without its own
+    pub fold: Fold,
+    /// Per-block folds, keyed by 1-based block number
+    pub block_folds: HashMap<usize, Fold>,
+    /// Evaluate with big integers instead of checked `u128`
+    pub exact: bool,
+    /// Print every block before the grand total
+    pub verbose: bool,
+}
+
+impl Options {
+    /// Parse `--read rows|columns-rtl|columns-ltr|bottom-up` and any number of
+    /// `--fold left|right` (all blocks) or `--fold N=left|right` (block N only),
+    /// `--exact` and `--verbose`.
+    /// Each binary passes its own default reading direction.
+    pub fn from_args(args: impl IntoIterator<Item = String>, direction: ReadingDirection) -> Result<Self, String> {
+        let mut options = Options { direction, fold: Fold::Left, block_folds: HashMap::new(), exact: false, verbose: false };
+
+        let mut args = args.into_iter();
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "--read" => {
+                    options.direction = args.next().ok_or("--read needs a direction")?.parse()?;
+                }
+                "--fold" => {
+                    let value = args.next().ok_or("--fold needs left, right or N=left|right")?;
+                    match value.split_once('=') {
+                        Some((block, fold)) => {
+                            let block = block
+                                .parse()
+                                .ok()
+                                .filter(|&b| b > 0)
+                                .ok_or_else(|| format!("'{}' is not a block number", block))?;
+                            options.block_folds.insert(block, fold.parse()?);
+                        }
+                        None => options.fold = value.parse()?,
+                    }
+                }
+                "--exact" => options.exact = true,
+                "--verbose" => options.verbose = true,
+                _ => return Err(format!("unknown argument '{}'", arg)),
+            }
+        }
+        Ok(options)
+    }
+
+    /// The fold to use for a block (1-based)
+    pub fn fold_for(&self, block: usize) -> Fold {
+        self.block_folds.get(&block).copied().unwrap_or(self.fold)
+    }
+}
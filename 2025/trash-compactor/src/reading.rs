@@ -0,0 +1,53 @@
+use std::fmt;
+use std::str::FromStr;
+
+/// How the digits of a problem block are assembled into numbers
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReadingDirection {
+    /// Each row is one number, rows taken top to bottom (Part 1)
+    RowWise,
+    /// Each column is one number with the top digit most significant,
+    /// columns taken right to left (cephalopod math, Part 2)
+    ColumnsRightToLeft,
+    /// As `ColumnsRightToLeft`, but columns taken left to right
+    ColumnsLeftToRight,
+    /// Each column is one number with the bottom digit most significant,
+    /// columns taken right to left
+    BottomToTop,
+}
+
+impl ReadingDirection {
+    pub const ALL: [ReadingDirection; 4] = [
+        ReadingDirection::RowWise,
+        ReadingDirection::ColumnsRightToLeft,
+        ReadingDirection::ColumnsLeftToRight,
+        ReadingDirection::BottomToTop,
+    ];
+
+    /// The command line name of this direction
+    pub fn name(self) -> &'static str {
+        match self {
+            ReadingDirection::RowWise => "rows",
+            ReadingDirection::ColumnsRightToLeft => "columns-rtl",
+            ReadingDirection::ColumnsLeftToRight => "columns-ltr",
+            ReadingDirection::BottomToTop => "bottom-up",
+        }
+    }
+}
+
+impl FromStr for ReadingDirection {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        ReadingDirection::ALL.into_iter().find(|d| d.name() == s).ok_or_else(|| {
+            let names: Vec<&str> = ReadingDirection::ALL.iter().map(|d| d.name()).collect();
+            format!("unknown reading direction '{}' (expected one of: {})", s, names.join(", "))
+        })
+    }
+}
+
+impl fmt::Display for ReadingDirection {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
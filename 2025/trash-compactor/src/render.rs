@@ -0,0 +1,33 @@
+//! Verbose rendering of a worksheet, one problem block at a time.
+//!
+//! Each block is redrawn as it was cut out of the worksheet, between `|`
+//! markers so padding and misaligned columns are visible, followed by the
+//! operands in the order they are read, the operator and the result.
+
+use crate::{Block, Number, Options, Worksheet};
+
+/// Print every block of the worksheet. Blocks that fail to evaluate show
+/// their error in place of a result, so all of them are still printed.
+pub fn print_blocks<N: Number>(worksheet: &Worksheet, options: &Options) {
+    for (i, block) in worksheet.blocks.iter().enumerate() {
+        print_block::<N>(block, i + 1, options);
+        println!();
+    }
+}
+
+fn print_block<N: Number>(block: &Block, number: usize, options: &Options) {
+    let fold = options.fold_for(number);
+
+    println!("Block {} (columns {}-{})", number, block.span.start + 1, block.span.end);
+    for row in &block.cells {
+        println!("  |{}|", row.iter().collect::<String>());
+    }
+    println!("  |{:<width$}|", block.operator, width = block.span.len());
+
+    println!("  operands ({}): {}", options.direction, block.operands(options.direction).join(", "));
+    println!("  operator: {} (fold {})", block.operator, fold);
+    match block.result::<N>(options.direction, fold) {
+        Ok(result) => println!("  result: {}", result),
+        Err(error) => println!("  error: {}", error),
+    }
+}
@@ -0,0 +1,150 @@
+use crate::{EvalError, Fold, Number, Operator, Options, ReadingDirection};
+use std::fmt;
+use std::ops::Range;
+
+/// One vertically-arranged problem: a run of columns between blank columns
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Block {
+    /// Columns the block occupies in the worksheet
+    pub span: Range<usize>,
+    /// The block's cells in every row above the operator row, padded with
+    /// spaces so every row is exactly `span.len()` wide
+    pub cells: Vec<Vec<char>>,
+    /// The block's slice of the last row, trimmed; see `Operator` for the
+    /// recognised symbols
+    pub operator: String,
+}
+
+impl Block {
+    /// The block's operands as written, in reading order. Rows or columns
+    /// with nothing in them are skipped.
+    pub fn operands(&self, direction: ReadingDirection) -> Vec<String> {
+        let width = self.span.len();
+        let operands: Vec<String> = match direction {
+            ReadingDirection::RowWise => self
+                .cells
+                .iter()
+                .map(|row| row.iter().collect::<String>().trim().to_string())
+                .collect(),
+            ReadingDirection::ColumnsRightToLeft => (0..width).rev().map(|col| self.column(col, false)).collect(),
+            ReadingDirection::ColumnsLeftToRight => (0..width).map(|col| self.column(col, false)).collect(),
+            ReadingDirection::BottomToTop => (0..width).rev().map(|col| self.column(col, true)).collect(),
+        };
+        operands.into_iter().filter(|op| !op.is_empty()).collect()
+    }
+
+    /// Apply the block's operator to its operands, grouped by `fold`.
+    /// Every operand must parse; nothing is skipped.
+    pub fn result<N: Number>(&self, direction: ReadingDirection, fold: Fold) -> Result<N, EvalError> {
+        let operator: Operator = self.operator.parse()?;
+        let numbers = self
+            .operands(direction)
+            .into_iter()
+            .map(|op| op.parse().map_err(|_| EvalError::BadOperand(op)))
+            .collect::<Result<Vec<N>, _>>()?;
+
+        operator.evaluate(fold, &numbers)
+    }
+
+    /// The non-blank characters in column `col` of the block (0 = leftmost),
+    /// top to bottom or bottom to top. Stray non-digits are kept so they fail
+    /// to parse instead of vanishing.
+    fn column(&self, col: usize, upwards: bool) -> String {
+        let chars = self.cells.iter().map(|row| row[col]).filter(|&c| c != ' ');
+        if upwards { chars.rev().collect() } else { chars.collect() }
+    }
+}
+
+/// A parsed worksheet: every problem block, left to right
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Worksheet {
+    pub blocks: Vec<Block>,
+}
+
+impl Worksheet {
+    /// Split the worksheet into problem blocks.
+    ///
+    /// Lines are padded to the widest line, the last non-empty line is the
+    /// operator row, and a column that is blank in every row (operator row
+    /// included) separates two problems. Each line is converted to a `Vec<char>`
+    /// once, so every column lookup is O(1).
+    pub fn parse(input: &str) -> Self {
+        let mut lines: Vec<&str> = input.lines().collect();
+        while lines.last().is_some_and(|l| l.trim().is_empty()) {
+            lines.pop();
+        }
+        let Some((operator_row, number_rows)) = lines.split_last() else {
+            return Worksheet { blocks: Vec::new() };
+        };
+
+        // Pad all lines to the same width
+        let width = lines.iter().map(|l| l.chars().count()).max().unwrap_or(0);
+        let pad = |line: &str| -> Vec<char> {
+            let mut chars: Vec<char> = line.chars().collect();
+            chars.resize(width, ' ');
+            chars
+        };
+        let grid: Vec<Vec<char>> = number_rows.iter().map(|l| pad(l)).collect();
+        let operators = pad(operator_row);
+
+        // Find problem boundaries: runs of columns that aren't blank in every row
+        let blank = |col: usize| operators[col] == ' ' && grid.iter().all(|row| row[col] == ' ');
+        let mut spans = Vec::new();
+        let mut start = None;
+        for col in 0..width {
+            match (start, blank(col)) {
+                (None, false) => start = Some(col),
+                (Some(s), true) => {
+                    spans.push(s..col);
+                    start = None;
+                }
+                _ => {}
+            }
+        }
+        if let Some(s) = start {
+            spans.push(s..width);
+        }
+
+        let blocks = spans
+            .into_iter()
+            .map(|span| Block {
+                cells: grid.iter().map(|row| row[span.clone()].to_vec()).collect(),
+                operator: operators[span.clone()].iter().collect::<String>().trim().to_string(),
+                span,
+            })
+            .collect();
+
+        Worksheet { blocks }
+    }
+
+    /// Sum of every block's result, or the first block that can't be evaluated.
+    /// A block whose result overflows the running total is reported too.
+    pub fn grand_total<N: Number>(&self, options: &Options) -> Result<N, BlockError> {
+        let mut total = N::zero();
+        for (i, block) in self.blocks.iter().enumerate() {
+            let error = |error| BlockError { block: i + 1, span: block.span.clone(), error };
+            let result: N = block.result(options.direction, options.fold_for(i + 1)).map_err(error)?;
+            total = total.checked_add(&result).ok_or_else(|| {
+                error(EvalError::Overflow { expression: format!("grand total {} + {}", total, result), number: N::NAME })
+            })?;
+        }
+        Ok(total)
+    }
+}
+
+/// A block that failed to evaluate
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BlockError {
+    /// 1-based position of the block, counting from the left
+    pub block: usize,
+    pub span: Range<usize>,
+    pub error: EvalError,
+}
+
+impl fmt::Display for BlockError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "block {} (columns {}-{}): {}", self.block, self.span.start + 1, self.span.end, self.error)
+    }
+}
+
+impl std::error::Error for BlockError {}